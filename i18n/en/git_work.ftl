@@ -8,12 +8,81 @@ no-notifications-desc = No new notifications
 show-all = Show all
 mark-all-read = Mark all read
 mark-as-read = Mark as read
+mark-repo-read = Mark all read in this repository
+done = Done
+unsubscribe = Unsubscribe
+snooze = Snooze
+snooze-one-hour = 1 hour
+snooze-this-evening = This evening
+snooze-tomorrow = Tomorrow
+snooze-next-week = Next week
+snooze-custom-placeholder = YYYY-MM-DD HH:MM
+subscription-subscribed = Subscribed to this thread
+subscription-ignored = Ignoring this thread
+subscription-none = Not subscribed; following through participation or watching
+subscription-loading = Loading subscription...
 refresh = Refresh
+undo = Undo
+save = Save for later
+unsave = Remove from saved
+saved = Saved
+no-saved = Nothing saved
+no-saved-desc = Save a notification to keep it here until you remove it
+select = Select
+select-all-visible = Select all visible
+selected-count = { $count } selected
+open-in-browser = Open in browser
+bulk-progress = Updating { $completed } of { $total }...
+bulk-failed = { $count ->
+    [one] { $count } request failed
+    *[other] { $count } requests failed
+}
+undo-read = { $count ->
+    [one] Marked as read
+    *[other] Marked { $count } notifications as read
+}
+undo-done = { $count ->
+    [one] Marked as done
+    *[other] Marked { $count } notifications as done
+}
+undo-unsubscribe = { $count ->
+    [one] Unsubscribed from thread
+    *[other] Unsubscribed from { $count } threads
+}
+pause-polling = Pause polling for 1 hour
+resume-polling = Resume polling
+open-inbox = Open github.com/notifications
+settings = Settings
+watched-repositories = Watched repositories
+loading-watched = Loading watched repositories...
+watch-all = All activity
+watch-participating = Participating
+watch-ignore = Ignore
+unwatch = Unwatch
+middle-click-action = Middle-click action
+opener-command = Open links with
+opener-command-placeholder = Default browser
+opener-command-help = For example "firefox -P work {"{url}"}" or "gh pr view --web {"{number}"} -R {"{repo}"}"
+label-allowlist = Labels to show
+label-allowlist-placeholder = All labels
+label-allowlist-help = Comma-separated, for example "bug, P0, needs-*"
 notifications-count = { $count ->
     [one] { $count } notification
     *[other] { $count } notifications
 }
 
+# Panel tooltip
+tooltip-unread = { $count } unread
+tooltip-review-requests = { $count ->
+    [one] { $count } review request
+    *[other] { $count } review requests
+}
+tooltip-mentions = { $count ->
+    [one] { $count } mention
+    *[other] { $count } mentions
+}
+tooltip-last-refreshed = last refreshed { $time }
+
 # Error messages
 error-no-token = GitHub token not found. Please set GITHUB_TOKEN environment variable.
 error-token-setup = To fix this:
@@ -23,6 +92,13 @@ error-token-step3 = 3. Restart the applet
 error-network = Network error occurred
 error-api = GitHub API error
 error-mark-read = Failed to mark as read
+error-mark-done = Failed to mark as done
+error-subscription = Failed to load subscription
+error-unsubscribe = Failed to unsubscribe
+error-snooze-time = Enter a future time as YYYY-MM-DD HH:MM
+error-watched-repos = Failed to load watched repositories
+error-watch-level = Failed to change watch level
+error-watch-level-unknown = Couldn't read the watch level
 
 # Notification reasons
 reason-assign = You were assigned
@@ -52,10 +128,38 @@ time-days-ago = { $days ->
     *[other] { $days } days ago
 }
 
+# Pull request states
+pr-open = Open
+pr-draft = Draft
+pr-merged = Merged
+pr-closed = Closed
+
+# CI status
+checks-passing = ✓ Checks passing
+checks-failing = ✗ Checks failing
+checks-pending = ● Checks pending
+
+# Copying
+copy = Copy
+copy-link = Link
+copy-reference = Reference
+copy-markdown = Markdown link
+
+# Reviews
+review-approved = Approved
+review-changes-requested = Changes requested
+review-required = Review required
+review-pending = Your review is pending
+review-done = You reviewed
+review-new-commits = New commits since your review
+review-satisfied = Review request satisfied
+
 # Notification types
 type-pull-request = Pull Request
 type-issue = Issue
 type-release = Release
 type-discussion = Discussion
+type-commit = Commit
+type-check-suite = Workflow Run
 type-security-alert = Security Alert
 type-repository-invitation = Repository Invitation
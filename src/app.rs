@@ -1,9 +1,23 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::config::Config;
+use crate::avatar;
+use crate::cache;
+use crate::config::{Config, PanelAction};
+use crate::enrichment::{
+    self, Batch, CheckState, Checks, Comment, Enrichment, Label, PullState, RateLimit, Review,
+    ReviewDecision,
+};
+use crate::fl;
 use crate::github::*;
+use crate::opener::{self, Target};
+use crate::snooze::{self, Snooze, SnoozePreset};
+use crate::state::{self, ReadOverride, SavedThread, State};
+use cosmic::applet::cosmic_panel_config::PanelAnchor;
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
-use cosmic::iced::{window::Id, Alignment, Length, Limits, Subscription, Task};
+use cosmic::iced::keyboard::Modifiers;
+use cosmic::iced::{
+    event, keyboard, window::Id, Alignment, Color, Length, Limits, Subscription, Task,
+};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
 use cosmic::prelude::*;
 use cosmic::theme::spacing;
@@ -11,6 +25,25 @@ use cosmic::widget;
 use octocrab::models::activity::Notification;
 use octocrab::models::NotificationId;
 use octocrab::Octocrab;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// How often notifications are refreshed in the background.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// How long "Pause polling" suspends background refreshes.
+const POLL_PAUSE: Duration = Duration::from_secs(60 * 60);
+/// How long destructive actions can be undone before they are sent to GitHub.
+const UNDO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum number of bulk requests in flight at once.
+const BULK_CONCURRENCY: usize = 4;
+/// Maximum number of enrichment requests in flight at once.
+const ENRICHMENT_CONCURRENCY: usize = 4;
+/// Number of subjects resolved by a single GraphQL query.
+const ENRICHMENT_BATCH_SIZE: usize = 25;
+/// GraphQL points left untouched for other clients sharing the token.
+const RATE_LIMIT_RESERVE: i64 = 200;
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -20,8 +53,22 @@ pub struct AppModel {
     core: cosmic::Core,
     /// The popup id.
     popup: Option<Id>,
+    /// The context menu popup id.
+    context_menu: Option<Id>,
+    /// Page shown in the popup.
+    page: Page,
     /// Configuration data that persists between application runs.
     config: Config,
+    /// Handle used to write configuration changes.
+    config_handler: Option<cosmic_config::Config>,
+    /// Local state that persists between application runs.
+    state: State,
+    /// Handle used to write state changes.
+    state_handler: Option<cosmic_config::Config>,
+    /// Label allowlist as typed in the settings, comma-separated
+    label_allowlist_input: String,
+    /// Localized labels for the panel actions, in `PanelAction::ALL` order.
+    panel_action_labels: Vec<String>,
     /// GitHub service for API interactions
     client: Option<Octocrab>,
     /// Current notifications
@@ -30,12 +77,68 @@ pub struct AppModel {
     is_loading: bool,
     /// Error state
     error_message: Option<String>,
+    /// Category of the current error, if any
+    error_kind: Option<ErrorKind>,
     /// Filter state
     show_all: bool,
     /// Last refresh time
-    last_refresh: Option<std::time::Instant>,
+    last_refresh: Option<Instant>,
+    /// When the displayed list was requested from GitHub
+    fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Background polling is suspended until this time
+    polling_paused_until: Option<Instant>,
     /// Unread count for the icon
     unread_count: usize,
+    /// Notification whose action menu is expanded
+    item_menu: Option<NotificationId>,
+    /// Known thread subscription states
+    subscriptions: HashMap<NotificationId, SubscriptionState>,
+    /// Threads whose subscription state failed to load
+    failed_subscriptions: HashSet<NotificationId>,
+    /// Action waiting for its undo window to expire before reaching GitHub
+    pending: Option<PendingAction>,
+    /// Whether the list shows selection checkboxes
+    selection_mode: bool,
+    /// Threads selected for bulk actions
+    selected: HashSet<NotificationId>,
+    /// Last toggled thread, where shift-click ranges start
+    selection_anchor: Option<NotificationId>,
+    /// Currently held keyboard modifiers
+    modifiers: Modifiers,
+    /// Progress of bulk requests in flight
+    bulk: Option<BulkProgress>,
+    /// Why the last read, done or unsubscribe request failed, kept across refreshes
+    action_error: Option<String>,
+    /// Subject details, keyed by subject API URL
+    enrichments: HashMap<String, Enrichment>,
+    /// Subjects whose details are being fetched
+    enriching: HashSet<String>,
+    /// REST responses behind the enrichments, kept for ETag revalidation
+    responses: cache::Responses,
+    /// Whether GraphQL failed, so that enrichment goes through REST for this session
+    graphql_unavailable: bool,
+    /// GraphQL rate limit reported by the last batch
+    rate_limit: Option<RateLimit>,
+    /// Login of the signed-in user, once known
+    viewer: Arc<tokio::sync::OnceCell<String>>,
+    /// Downloaded avatars, keyed by sized URL
+    avatars: HashMap<String, widget::image::Handle>,
+    /// Avatars being downloaded or that failed to download
+    requested_avatars: HashSet<String>,
+    /// Custom snooze time being typed in an item menu
+    snooze_input: String,
+    /// Whether the custom snooze time couldn't be parsed
+    snooze_input_invalid: bool,
+    /// Last copy of threads kept unread locally, which GitHub's unread list leaves out
+    fetched_threads: HashMap<NotificationId, Notification>,
+    /// Repositories the user is watching
+    watched_repos: Vec<WatchedRepo>,
+    /// Loading state of the watched repositories
+    is_loading_watched: bool,
+    /// Why the watched repositories couldn't be listed
+    watched_error: Option<String>,
+    /// Repositories whose last watch level change failed
+    failed_watch_levels: HashSet<String>,
 }
 
 /// Messages emitted by the application and its widgets.
@@ -43,12 +146,56 @@ pub struct AppModel {
 pub enum Message {
     TogglePopup,
     PopupClosed(Id),
+    OpenContextMenu,
+    PanelAction(PanelAction),
+    ShowPage(Page),
+    ToggleSaved(NotificationId),
+    OpenUrl(Target),
+    CopyToClipboard(String),
+    SetOpenerCommand(String),
+    SetLabelAllowlist(String),
+    SetMiddleClickAction(usize),
+    PollTick,
     RefreshNotifications,
-    NotificationsLoaded(Result<Vec<Notification>, String>),
+    NotificationsLoaded(chrono::DateTime<chrono::Utc>, Result<Vec<Notification>, Error>),
     OpenNotification(Notification),
+    UrlResolved(Notification, Result<Option<String>, String>),
     MarkAsRead(NotificationId),
+    MarkAsUnread(NotificationId),
     MarkAllAsRead,
-    NotificationMarkedAsRead(Result<Option<NotificationId>, String>),
+    MarkRepoAsRead(String),
+    NotificationMarkedAsRead(Option<NotificationId>, Result<(), String>),
+    MarkAsDone(NotificationId),
+    NotificationDone(Result<NotificationId, String>),
+    ToggleItemMenu(NotificationId),
+    SubscriptionLoaded(NotificationId, Result<SubscriptionState, String>),
+    Unsubscribe(NotificationId),
+    Unsubscribed(Result<NotificationId, String>),
+    Snooze(NotificationId, SnoozePreset),
+    SnoozeInput(String),
+    SnoozeCustom(NotificationId),
+    ThreadFetched(Result<Notification, String>),
+    Enriched(String, Result<Enrichment, String>),
+    EnrichedBatch(Vec<Notification>, Result<Batch, Error>),
+    AvatarLoaded(String, Result<Vec<u8>, String>),
+    Undo,
+    /// Undo clicked on the desktop notification of the pending action expiring at this time.
+    UndoFromNotification(Instant),
+    UndoTick,
+    ToggleSelectionMode,
+    ToggleSelected(NotificationId, bool),
+    SelectAllVisible,
+    ModifiersChanged(Modifiers),
+    Bulk(BulkAction),
+    BulkItemFinished(PendingKind, NotificationId, Result<(), String>),
+    DismissBulkReport,
+    DismissActionError,
+    LoadWatchedRepos,
+    WatchedReposLoaded(Result<Vec<WatchedRepo>, String>),
+    SetWatchLevel(String, WatchLevel),
+    WatchLevelSet(String, Result<WatchLevel, String>),
+    Unwatch(String),
+    Unwatched(String, Result<(), String>),
     ToggleShowAll(bool),
     UpdateConfig(Config),
 }
@@ -92,18 +239,39 @@ impl cosmic::Application for AppModel {
             Ok(client)
         };
 
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let (enrichments, responses) = cache::load();
+        let state_handler = cosmic_config::Config::new_state(Self::APP_ID, State::VERSION).ok();
+
         // Construct the app model with the runtime's core.
         let mut app = AppModel {
             core,
             client: client().ok(),
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
+            config: config_handler
+                .as_ref()
+                .map(|context| match Config::get_entry(context) {
                     Ok(config) => config,
                     Err((_errors, config)) => config,
                 })
                 .unwrap_or_default(),
+            config_handler,
+            state: state_handler
+                .as_ref()
+                .map(|context| match State::get_entry(context) {
+                    Ok(state) => state,
+                    Err((_errors, state)) => state,
+                })
+                .unwrap_or_default(),
+            state_handler,
+            panel_action_labels: PanelAction::ALL
+                .iter()
+                .map(|action| panel_action_label(*action, false))
+                .collect(),
+            enrichments,
+            responses,
             ..Default::default()
         };
+        app.label_allowlist_input = app.config.label_allowlist.join(", ");
 
         let task = if app.client.is_some() {
             app.is_loading = true;
@@ -114,10 +282,11 @@ impl cosmic::Application for AppModel {
             app.error_message = Some(
                 "GitHub token not found. Please set GITHUB_TOKEN environment variable.".to_string(),
             );
+            app.error_kind = Some(ErrorKind::Auth);
             Task::none()
         };
 
-        (app, task)
+        (app, Task::batch([task, Task::future(avatar::prune()).discard()]))
     }
 
     fn on_close_requested(&self, id: Id) -> Option<Message> {
@@ -129,23 +298,55 @@ impl cosmic::Application for AppModel {
     /// Application events will be processed through the view. Any messages emitted by
     /// events received by widgets will be passed to the update method.
     fn view(&self) -> Element<'_, Self::Message> {
-        let icon = if self.unread_count > 0 {
-            "mail-unread-symbolic"
+        let icon_name = self.panel_state().icon_name();
+
+        let button = if self.unread_count == 0 {
+            self.core.applet.icon_button(icon_name)
         } else {
-            "mail-read-symbolic"
+            self.panel_button_with_count(icon_name)
         };
 
-        self.core
-            .applet
-            .icon_button(icon)
-            .on_press(Message::TogglePopup)
-            .into()
+        // Keep the tooltip on the side of the button facing away from the panel edge.
+        let position = match self.core.applet.anchor {
+            PanelAnchor::Top => widget::tooltip::Position::Bottom,
+            PanelAnchor::Bottom => widget::tooltip::Position::Top,
+            PanelAnchor::Left => widget::tooltip::Position::Right,
+            PanelAnchor::Right => widget::tooltip::Position::Left,
+        };
+
+        let button = widget::mouse_area(button.on_press(Message::TogglePopup))
+            .on_right_press(Message::OpenContextMenu)
+            .on_middle_press(Message::PanelAction(self.config.middle_click_action));
+
+        widget::tooltip(button, widget::text(self.tooltip_summary()), position).into()
     }
 
-    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+    fn view_window(&self, id: Id) -> Element<'_, Self::Message> {
+        if self.context_menu == Some(id) {
+            return self.context_menu_view();
+        }
+
         let header = widget::row()
             .push(widget::text("GitHub Notifications").size(spacing().space_s))
             .push(widget::horizontal_space().width(Length::Fill))
+            .push(
+                widget::button::icon(widget::icon::from_name("selection-mode-symbolic"))
+                    .tooltip(fl!("select"))
+                    .padding([spacing().space_xxxs, spacing().space_xxs])
+                    .on_press(Message::ToggleSelectionMode),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("starred-symbolic"))
+                    .tooltip(fl!("saved"))
+                    .padding([spacing().space_xxxs, spacing().space_xxs])
+                    .on_press(Message::ShowPage(Page::Saved)),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("view-list-symbolic"))
+                    .tooltip(fl!("watched-repositories"))
+                    .padding([spacing().space_xxxs, spacing().space_xxs])
+                    .on_press(Message::ShowPage(Page::Watching)),
+            )
             .push(
                 widget::button::icon(widget::icon::from_name("checkbox-checked-symbolic"))
                     .tooltip("Mark all read")
@@ -162,7 +363,13 @@ impl cosmic::Application for AppModel {
             .spacing(spacing().space_xxs)
             .align_y(Alignment::Center);
 
-        let content = if let Some(error) = &self.error_message {
+        let content = if self.page == Page::Settings {
+            self.settings_view()
+        } else if self.page == Page::Watching {
+            self.watching_view()
+        } else if self.page == Page::Saved {
+            self.saved_view()
+        } else if let Some(error) = &self.error_message {
             widget::column()
                 .push(header)
                 .push(
@@ -196,7 +403,8 @@ impl cosmic::Application for AppModel {
                     .class(cosmic::theme::Container::Card),
                 )
                 .spacing(spacing().space_xs)
-        } else if self.is_loading {
+        } else if self.is_loading && self.last_refresh.is_none() {
+            // Later refreshes keep the list up, so polling doesn't interrupt the user.
             widget::column()
                 .push(header)
                 .push(
@@ -210,7 +418,7 @@ impl cosmic::Application for AppModel {
                     .class(cosmic::theme::Container::Card),
                 )
                 .spacing(spacing().space_xs)
-        } else if self.notifications.is_empty() {
+        } else if self.visible_notifications().next().is_none() {
             widget::column()
                 .push(header)
                 .push(
@@ -229,7 +437,7 @@ impl cosmic::Application for AppModel {
         } else {
             let controls = widget::row()
                 .push(
-                    widget::text(format!("{} notifications", self.notifications.len()))
+                    widget::text(format!("{} notifications", self.visible_notifications().count()))
                         .size(spacing().space_xs),
                 )
                 .push(widget::horizontal_space().width(Length::Fill))
@@ -246,8 +454,30 @@ impl cosmic::Application for AppModel {
                 .padding(spacing().space_xxs);
 
             let mut notifications_list = widget::column().spacing(spacing().space_xxxs);
-            for notification in &self.notifications {
-                notifications_list = notifications_list.push(self.notification_item(notification));
+            for (repository, items) in self.grouped_notifications() {
+                notifications_list =
+                    notifications_list.push_maybe(repository.map(|name| {
+                        let has_unread = items.iter().any(|n| n.unread);
+                        widget::row()
+                            .push(widget::text(name).size(spacing().space_xs))
+                            .push(widget::horizontal_space().width(Length::Fill))
+                            .push(
+                                widget::button::icon(widget::icon::from_name(
+                                    "checkbox-checked-symbolic",
+                                ))
+                                .tooltip(fl!("mark-repo-read"))
+                                .padding(spacing().space_xxxs)
+                                .on_press_maybe(
+                                    has_unread.then(|| Message::MarkRepoAsRead(name.clone())),
+                                ),
+                            )
+                            .align_y(Alignment::Center)
+                            .padding([spacing().space_xxxs, spacing().space_xxs])
+                    }));
+                for notification in items {
+                    notifications_list =
+                        notifications_list.push(self.notification_item(notification));
+                }
             }
             let notifications = widget::scrollable(
                 widget::container(notifications_list)
@@ -257,11 +487,21 @@ impl cosmic::Application for AppModel {
 
             widget::column()
                 .push(header)
+                .push_maybe(self.selection_mode.then(|| self.selection_bar()))
                 .push(notifications)
                 .push(controls)
                 .spacing(spacing().space_xxs)
         };
 
+        let content = if self.page == Page::Inbox {
+            content
+                .push_maybe(self.action_error_bar())
+                .push_maybe(self.bulk_report())
+                .push_maybe(self.undo_bar())
+        } else {
+            content
+        };
+
         self.core
             .applet
             .popup_container(
@@ -278,12 +518,33 @@ impl cosmic::Application for AppModel {
     /// emit messages to the application through a channel. They are started at the
     /// beginning of the application, and persist through its lifetime.
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             // Watch for application configuration changes.
             self.core()
                 .watch_config::<Config>(Self::APP_ID)
                 .map(|update| Message::UpdateConfig(update.config)),
-        ])
+            // Refresh notifications in the background.
+            cosmic::iced::time::every(POLL_INTERVAL).map(|_| Message::PollTick),
+        ];
+
+        // Track modifiers for shift-click range selection.
+        if self.selection_mode {
+            subscriptions.push(event::listen_with(|event, _status, _id| match event {
+                cosmic::iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                    Some(Message::ModifiersChanged(modifiers))
+                }
+                _ => None,
+            }));
+        }
+
+        // Watch for the undo window to expire.
+        if self.pending.is_some() {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_millis(250)).map(|_| Message::UndoTick),
+            );
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     /// Handles messages emitted by the application and its widgets.
@@ -295,35 +556,244 @@ impl cosmic::Application for AppModel {
                 self.config = config;
             }
             Message::TogglePopup => {
-                return if let Some(p) = self.popup.take() {
-                    destroy_popup(p)
+                if let Some(p) = self.context_menu.take() {
+                    tasks.push(destroy_popup(p));
+                }
+                if let Some(p) = self.popup.take() {
+                    tasks.push(destroy_popup(p));
+                } else {
+                    self.page = Page::Inbox;
+                    tasks.push(self.open_popup());
+                }
+            }
+            Message::PopupClosed(id) => {
+                if self.popup.as_ref() == Some(&id) {
+                    self.popup = None;
+                }
+                if self.context_menu.as_ref() == Some(&id) {
+                    self.context_menu = None;
+                }
+            }
+            Message::OpenContextMenu => {
+                if let Some(p) = self.popup.take() {
+                    tasks.push(destroy_popup(p));
+                }
+                if let Some(p) = self.context_menu.take() {
+                    tasks.push(destroy_popup(p));
                 } else {
                     let new_id = Id::unique();
-                    self.popup.replace(new_id);
-                    let mut popup_settings = self.core.applet.get_popup_settings(
+                    self.context_menu.replace(new_id);
+                    let popup_settings = self.core.applet.get_popup_settings(
                         self.core.main_window_id().unwrap(),
                         new_id,
                         None,
                         None,
                         None,
                     );
-                    popup_settings.positioner.size_limits = Limits::NONE
-                        .max_width(1000.0)
-                        .min_width(1000.0)
-                        .min_height(1000.0)
-                        .max_height(1000.0);
-                    get_popup(popup_settings)
+                    tasks.push(get_popup(popup_settings));
                 }
             }
-            Message::PopupClosed(id) => {
-                if self.popup.as_ref() == Some(&id) {
-                    self.popup = None;
+            Message::PanelAction(action) => {
+                if let Some(p) = self.context_menu.take() {
+                    tasks.push(destroy_popup(p));
+                }
+                match action {
+                    PanelAction::Refresh => {
+                        tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    }
+                    PanelAction::MarkAllRead => {
+                        tasks.push(cosmic::task::message(Message::MarkAllAsRead));
+                    }
+                    PanelAction::PausePolling => {
+                        self.polling_paused_until = if self.is_polling_paused() {
+                            None
+                        } else {
+                            Some(Instant::now() + POLL_PAUSE)
+                        };
+                    }
+                    PanelAction::OpenInbox => {
+                        let target = Target::new("https://github.com/notifications", None);
+                        tasks.push(self.open_target(target));
+                    }
+                    PanelAction::Settings => {
+                        self.page = Page::Settings;
+                        if self.popup.is_none() {
+                            tasks.push(self.open_popup());
+                        }
+                    }
+                }
+            }
+            Message::ToggleSaved(notification_id) => {
+                let mut saved = self.state.saved.clone();
+                if saved.iter().any(|saved| saved.id == notification_id) {
+                    saved.retain(|saved| saved.id != notification_id);
+                } else if let Some(notification) =
+                    self.notifications.iter().find(|n| n.id == notification_id)
+                {
+                    saved.insert(
+                        0,
+                        SavedThread {
+                            id: notification.id,
+                            title: notification.subject.title.clone(),
+                            url: get_notification_url(notification),
+                            repository: notification.repository.full_name.clone(),
+                            saved_at: chrono::Utc::now(),
+                        },
+                    );
+                }
+                self.save_saved(saved);
+            }
+            Message::OpenUrl(target) => {
+                return self.open_target(target);
+            }
+            Message::CopyToClipboard(text) => {
+                return cosmic::iced::clipboard::write(text);
+            }
+            Message::SetOpenerCommand(command) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(error) = self.config.set_opener_command(handler, command) {
+                        eprintln!("failed to save opener command: {error}");
+                    }
+                }
+                None => self.config.opener_command = command,
+            },
+            Message::SetLabelAllowlist(input) => {
+                let allowlist: Vec<String> = input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect();
+                self.label_allowlist_input = input;
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(error) = self.config.set_label_allowlist(handler, allowlist) {
+                            eprintln!("failed to save label allowlist: {error}");
+                        }
+                    }
+                    None => self.config.label_allowlist = allowlist,
+                }
+            }
+            Message::ShowPage(page) => {
+                self.page = page;
+                if page == Page::Watching && self.watched_repos.is_empty() {
+                    tasks.push(cosmic::task::message(Message::LoadWatchedRepos));
+                }
+            }
+            Message::LoadWatchedRepos => {
+                if let Some(client) = &self.client {
+                    self.is_loading_watched = true;
+                    let client = client.clone();
+                    return Task::perform(
+                        async move {
+                            list_watched_repos(&client)
+                                .await
+                                .map_err(|e| e.to_string())
+                        },
+                        |result| cosmic::Action::App(Message::WatchedReposLoaded(result)),
+                    );
+                }
+            }
+            Message::WatchedReposLoaded(result) => {
+                self.is_loading_watched = false;
+                match result {
+                    Ok(repos) => {
+                        self.watched_repos = repos;
+                        self.watched_error = None;
+                    }
+                    Err(error) => {
+                        self.watched_error =
+                            Some(format!("{}: {}", fl!("error-watched-repos"), error));
+                    }
+                }
+            }
+            Message::SetWatchLevel(full_name, level) => {
+                if let Some(client) = &self.client {
+                    let client = client.clone();
+                    return Task::perform(
+                        async move {
+                            let result = set_watch_level(&client, &full_name, level)
+                                .await
+                                .map(|()| level)
+                                .map_err(|e| e.to_string());
+                            (full_name, result)
+                        },
+                        |(full_name, result)| {
+                            cosmic::Action::App(Message::WatchLevelSet(full_name, result))
+                        },
+                    );
+                }
+            }
+            Message::WatchLevelSet(full_name, result) => match result {
+                Ok(level) => {
+                    self.failed_watch_levels.remove(&full_name);
+                    if let Some(repo) = self
+                        .watched_repos
+                        .iter_mut()
+                        .find(|repo| repo.full_name == full_name)
+                    {
+                        repo.level = Some(level);
+                    }
+                }
+                Err(error) => {
+                    eprintln!("failed to set watch level for {full_name}: {error}");
+                    self.failed_watch_levels.insert(full_name);
+                }
+            },
+            // Unwatching sends the same request as Participating, but also drops the
+            // repository from the list, as GitHub does.
+            Message::Unwatch(full_name) => {
+                if let Some(client) = &self.client {
+                    let client = client.clone();
+                    return Task::perform(
+                        async move {
+                            let result =
+                                set_watch_level(&client, &full_name, WatchLevel::Participating)
+                                    .await
+                                    .map_err(|e| e.to_string());
+                            (full_name, result)
+                        },
+                        |(full_name, result)| {
+                            cosmic::Action::App(Message::Unwatched(full_name, result))
+                        },
+                    );
+                }
+            }
+            Message::Unwatched(full_name, result) => match result {
+                Ok(()) => {
+                    self.failed_watch_levels.remove(&full_name);
+                    self.watched_repos.retain(|repo| repo.full_name != full_name);
+                }
+                Err(error) => {
+                    eprintln!("failed to unwatch {full_name}: {error}");
+                    self.failed_watch_levels.insert(full_name);
+                }
+            },
+            Message::SetMiddleClickAction(index) => {
+                if let Some(action) = PanelAction::ALL.get(index) {
+                    match &self.config_handler {
+                        Some(handler) => {
+                            if let Err(error) =
+                                self.config.set_middle_click_action(handler, *action)
+                            {
+                                eprintln!("failed to save middle-click action: {error}");
+                            }
+                        }
+                        None => self.config.middle_click_action = *action,
+                    }
+                }
+            }
+            Message::PollTick => {
+                tasks.push(self.wake_snoozed());
+                if !self.is_loading && !self.is_polling_paused() {
+                    self.polling_paused_until = None;
+                    tasks.push(cosmic::task::message(Message::RefreshNotifications));
                 }
             }
             Message::RefreshNotifications => {
                 if let Some(client) = &self.client {
                     self.is_loading = true;
-                    self.error_message = None;
+                    let requested_at = chrono::Utc::now();
                     let all = self.show_all;
                     let client = client.clone();
                     return Task::perform(
@@ -336,99 +806,443 @@ impl cosmic::Application for AppModel {
                                 .send()
                                 .await
                                 .map(|r| r.items)
-                                .map_err(|e| e.to_string())
+                                .map_err(Error::from)
+                        },
+                        move |result| {
+                            cosmic::Action::App(Message::NotificationsLoaded(requested_at, result))
                         },
-                        |result| cosmic::Action::App(Message::NotificationsLoaded(result)),
                     );
                 }
             }
-            Message::NotificationsLoaded(result) => {
+            Message::NotificationsLoaded(requested_at, result) => {
+                // A slow refresh mustn't replace the list of one requested after it.
+                if self.fetched_at.is_some_and(|fetched_at| fetched_at > requested_at) {
+                    return Task::none();
+                }
                 self.is_loading = false;
-                self.last_refresh = Some(std::time::Instant::now());
+                self.last_refresh = Some(Instant::now());
                 match result {
                     Ok(notifications) => {
-                        self.unread_count = notifications.iter().filter(|n| n.unread).count();
                         self.notifications = notifications;
+                        self.fetched_at = Some(requested_at);
+                        // Reuse the last copy of threads kept unread locally instead of
+                        // fetching them on every poll; new activity brings them back anyway.
+                        for thread in self.fetched_threads.values() {
+                            if !self.notifications.iter().any(|n| n.id == thread.id) {
+                                self.notifications.push(thread.clone());
+                            }
+                        }
+                        self.notifications.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                        // Threads waiting to be marked done haven't left GitHub's inbox yet.
+                        if let Some(pending) = &self.pending {
+                            if pending.kind.removes_threads() {
+                                self.notifications.retain(|n| {
+                                    !pending.notifications.iter().any(|p| p.id == n.id)
+                                });
+                            }
+                        }
+                        let missing = self.reconcile_read_overrides(true);
+                        tasks.push(self.fetch_threads(missing));
+                        tasks.push(self.enrich_notifications());
+                        tasks.push(self.load_avatars());
                         self.error_message = None;
+                        self.error_kind = None;
                     }
                     Err(error) => {
-                        self.error_message = Some(error);
+                        self.error_kind = Some(error.kind);
+                        self.error_message = Some(error.message);
                     }
                 }
             }
             Message::OpenNotification(notification) => {
                 if let Some(client) = &self.client {
-                    if let Some(url) = get_notification_url(&notification) {
-                        let _ = open::that_detached(url);
+                    // Link straight to the comment that triggered the notification, or the
+                    // release page.
+                    let comment_url = self
+                        .enrichment(&notification)
+                        .and_then(|e| e.comment.as_ref())
+                        .and_then(|comment| comment.html_url.clone());
+                    let needs_resolving =
+                        matches!(notification.subject.r#type.as_str(), "Discussion" | "Release")
+                            || notification.subject.latest_comment_url.as_ref()
+                                != notification.subject.url.as_ref();
+
+                    if let Some(url) = comment_url {
+                        let repo = notification.repository.full_name.clone();
+                        tasks.push(self.open_target(Target::new(url, repo)));
+                    } else if needs_resolving {
+                        let client = client.clone();
+                        let resolving = notification.clone();
+                        let opening = notification.clone();
+                        tasks.push(Task::perform(
+                            async move {
+                                resolve_notification_url(&client, &resolving)
+                                    .await
+                                    .map_err(|e| e.to_string())
+                            },
+                            move |result| {
+                                cosmic::Action::App(Message::UrlResolved(opening, result))
+                            },
+                        ));
+                    } else if let Some(target) = Target::for_notification(&notification) {
+                        tasks.push(self.open_target(target));
                     }
 
                     // Mark as read if it was unread
                     if notification.unread {
-                        let notification_id = notification.id.clone();
+                        tasks.push(cosmic::task::message(Message::MarkAsRead(notification.id)));
+                    }
+                }
+            }
+            Message::UrlResolved(notification, result) => {
+                let target = match result {
+                    Ok(Some(url)) => Some(Target::new(url, notification.repository.full_name)),
+                    Ok(None) => Target::for_notification(&notification),
+                    Err(error) => {
+                        eprintln!("failed to resolve the notification link: {error}");
+                        Target::for_notification(&notification)
+                    }
+                };
+                if let Some(target) = target {
+                    tasks.push(self.open_target(target));
+                }
+            }
+            Message::MarkAsRead(notification_id) => {
+                if self.client.is_some() {
+                    return self.begin_pending(PendingKind::Read, vec![notification_id]);
+                }
+            }
+            Message::MarkAsUnread(notification_id) => {
+                // GitHub has no API for this, so it only lives in the local overlay.
+                self.override_read_state([notification_id], true);
+            }
+            Message::MarkAllAsRead => {
+                if self.client.is_some() {
+                    let ids = self.notifications.iter().map(|n| n.id).collect();
+                    return self.begin_pending(PendingKind::MarkAllRead, ids);
+                }
+            }
+            Message::MarkRepoAsRead(repository) => {
+                if self.client.is_some() {
+                    let ids = self
+                        .notifications
+                        .iter()
+                        .filter(|n| n.repository.full_name.as_ref() == Some(&repository))
+                        .map(|n| n.id)
+                        .collect();
+                    return self.begin_pending(PendingKind::MarkRepoRead, ids);
+                }
+            }
+            Message::NotificationMarkedAsRead(notification_id, result) => match result {
+                Ok(()) => {
+                    if notification_id.is_none() {
+                        tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    }
+                }
+                Err(error) => {
+                    // Drop the optimistic overrides so GitHub's state shows through again.
+                    let mut overrides = self.state.read_overrides.clone();
+                    match notification_id {
+                        Some(id) => {
+                            overrides.remove(&id);
+                        }
+                        None => overrides.retain(|_, o| o.unread),
+                    }
+                    self.save_read_overrides(overrides);
+                    tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    self.action_error = Some(format!("Failed to mark as read: {}", error));
+                }
+            },
+            Message::MarkAsDone(notification_id) => {
+                if self.client.is_some() {
+                    return self.begin_pending(PendingKind::Done, vec![notification_id]);
+                }
+            }
+            Message::NotificationDone(result) => match result {
+                Ok(notification_id) => {
+                    self.notifications.retain(|n| n.id != notification_id);
+                    self.refresh_unread_count();
+                }
+                Err(error) => {
+                    tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    self.action_error = Some(format!("{}: {}", fl!("error-mark-done"), error));
+                }
+            },
+            Message::ToggleItemMenu(notification_id) => {
+                self.snooze_input_invalid = false;
+                if self.item_menu == Some(notification_id) {
+                    self.item_menu = None;
+                } else {
+                    self.item_menu = Some(notification_id);
+                    if let Some(client) = &self.client {
                         let client = client.clone();
                         return Task::perform(
                             async move {
-                                client
-                                    .activity()
-                                    .notifications()
-                                    .mark_as_read(notification_id.into())
+                                get_thread_subscription(&client, notification_id)
                                     .await
-                                    .map_err(|e| e.to_string())?;
-                                Ok(Some(notification_id))
+                                    .map_err(|e| e.to_string())
+                            },
+                            move |result| {
+                                cosmic::Action::App(Message::SubscriptionLoaded(
+                                    notification_id,
+                                    result,
+                                ))
                             },
-                            |result| cosmic::Action::App(Message::NotificationMarkedAsRead(result)),
                         );
                     }
                 }
             }
-            Message::MarkAsRead(notification_id) => {
-                if let Some(client) = &self.client {
-                    let client = client.clone();
-                    return Task::perform(
-                        async move {
-                            client
-                                .activity()
-                                .notifications()
-                                .mark_as_read(notification_id.into())
-                                .await
-                                .map_err(|e| e.to_string())?;
-                            Ok(Some(notification_id))
-                        },
-                        |result| cosmic::Action::App(Message::NotificationMarkedAsRead(result)),
-                    );
+            Message::SubscriptionLoaded(notification_id, result) => match result {
+                Ok(state) => {
+                    self.failed_subscriptions.remove(&notification_id);
+                    self.subscriptions.insert(notification_id, state);
                 }
-            }
-            Message::MarkAllAsRead => {
-                if let Some(client) = &self.client {
-                    let client = client.clone();
-                    return Task::perform(
-                        async move {
-                            client
-                                .activity()
-                                .notifications()
-                                .mark_all_as_read(None)
-                                .await
-                                .map_err(|e| e.to_string())?;
-                            Ok(None)
-                        },
-                        |result| cosmic::Action::App(Message::NotificationMarkedAsRead(result)),
-                    );
+                Err(error) => {
+                    eprintln!("failed to load subscription of {notification_id}: {error}");
+                    self.failed_subscriptions.insert(notification_id);
+                }
+            },
+            Message::Unsubscribe(notification_id) => {
+                if self.client.is_some() {
+                    return self.begin_pending(PendingKind::Unsubscribe, vec![notification_id]);
                 }
             }
-            Message::NotificationMarkedAsRead(result) => match result {
-                Ok(None) => {
+            Message::Unsubscribed(result) => match result {
+                Ok(notification_id) => {
+                    self.subscriptions
+                        .insert(notification_id, SubscriptionState::Ignored);
+                    self.notifications.retain(|n| n.id != notification_id);
+                    self.refresh_unread_count();
+                }
+                Err(error) => {
                     tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    self.action_error = Some(format!("{}: {}", fl!("error-unsubscribe"), error));
+                }
+            },
+            Message::Snooze(notification_id, preset) => {
+                let until = preset.wake_time(chrono::Local::now());
+                self.snooze(&[notification_id], until);
+            }
+            Message::SnoozeInput(input) => {
+                self.snooze_input = input;
+                self.snooze_input_invalid = false;
+            }
+            Message::SnoozeCustom(notification_id) => {
+                match snooze::parse_custom(&self.snooze_input, chrono::Utc::now()) {
+                    Some(until) => {
+                        self.snooze_input.clear();
+                        self.snooze_input_invalid = false;
+                        self.snooze(&[notification_id], until);
+                    }
+                    None => {
+                        self.snooze_input_invalid = true;
+                    }
                 }
-                Ok(Some(notification_id)) => {
+            }
+            Message::ThreadFetched(result) => match result {
+                Ok(notification) => {
+                    self.notifications.retain(|n| n.id != notification.id);
+                    self.fetched_threads.insert(notification.id, notification.clone());
+                    self.notifications.push(notification);
                     self.notifications
-                        .iter_mut()
-                        .find(|n| n.id == notification_id)
-                        .map(|n| n.unread = !n.unread);
+                        .sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                    self.reconcile_read_overrides(false);
+                    return Task::batch([self.enrich_notifications(), self.load_avatars()]);
+                }
+                Err(error) => {
+                    eprintln!("failed to fetch thread: {error}");
+                }
+            },
+            Message::Enriched(key, result) => {
+                self.enriching.remove(&key);
+                match result {
+                    Ok(enrichment) => {
+                        self.enrichments.insert(key, enrichment);
+                        tasks.push(self.load_avatars());
+                    }
+                    Err(error) => {
+                        eprintln!("failed to fetch details of {key}: {error}");
+                    }
+                }
+                tasks.push(self.save_enrichments());
+            }
+            Message::EnrichedBatch(notifications, result) => {
+                for key in notifications.iter().filter_map(enrichment::cache_key) {
+                    self.enriching.remove(&key);
+                }
+                match result {
+                    Ok(batch) => {
+                        if batch.rate_limit.is_some() {
+                            self.rate_limit = batch.rate_limit;
+                        }
+                        for (key, result) in batch.enrichments {
+                            match result {
+                                Ok(enrichment) => {
+                                    self.enrichments.insert(key, enrichment);
+                                }
+                                Err(error) => {
+                                    eprintln!("failed to fetch details of {key}: {error}");
+                                }
+                            }
+                        }
+                        tasks.push(self.load_avatars());
+                        tasks.push(self.save_enrichments());
+                    }
+                    // Try again on the next refresh.
+                    Err(error) if error.kind == ErrorKind::Offline => {
+                        eprintln!("failed to fetch details: {error}");
+                    }
+                    Err(error) => {
+                        eprintln!("GraphQL enrichment failed, falling back to REST: {error}");
+                        self.graphql_unavailable = true;
+                        return self.enrich_notifications();
+                    }
+                }
+            }
+            Message::AvatarLoaded(url, result) => match result {
+                Ok(bytes) => {
+                    self.avatars
+                        .insert(url, widget::image::Handle::from_bytes(bytes));
                 }
                 Err(error) => {
-                    self.error_message = Some(format!("Failed to mark as read: {}", error));
+                    eprintln!("failed to download avatar {url}: {error}");
                 }
             },
+            Message::ToggleSelectionMode => {
+                self.selection_mode = !self.selection_mode;
+                self.selected.clear();
+                self.selection_anchor = None;
+            }
+            Message::ToggleSelected(notification_id, checked) => {
+                let anchor = self.selection_anchor.filter(|_| self.modifiers.shift());
+                let range = anchor.and_then(|anchor| {
+                    // Ranges follow the on-screen order, which is grouped by repository.
+                    let visible: Vec<_> = self
+                        .grouped_notifications()
+                        .into_iter()
+                        .flat_map(|(_, items)| items)
+                        .map(|n| n.id)
+                        .collect();
+                    let start = visible.iter().position(|id| *id == anchor)?;
+                    let end = visible.iter().position(|id| *id == notification_id)?;
+                    Some(visible[start.min(end)..=start.max(end)].to_vec())
+                });
+
+                for id in range.unwrap_or_else(|| vec![notification_id]) {
+                    if checked {
+                        self.selected.insert(id);
+                    } else {
+                        self.selected.remove(&id);
+                    }
+                }
+                self.selection_anchor = Some(notification_id);
+            }
+            Message::SelectAllVisible => {
+                let visible: HashSet<_> = self.visible_notifications().map(|n| n.id).collect();
+                if visible.is_subset(&self.selected) {
+                    self.selected.clear();
+                } else {
+                    self.selected = visible;
+                }
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            Message::Bulk(action) => {
+                let selected: Vec<_> = self
+                    .visible_notifications()
+                    .filter(|n| self.selected.contains(&n.id))
+                    .map(|n| n.id)
+                    .collect();
+                self.selected.clear();
+                self.selection_anchor = None;
+
+                match action {
+                    BulkAction::MarkRead => {
+                        return self.begin_pending(PendingKind::Read, selected);
+                    }
+                    BulkAction::Done => {
+                        return self.begin_pending(PendingKind::Done, selected);
+                    }
+                    BulkAction::Unsubscribe => {
+                        return self.begin_pending(PendingKind::Unsubscribe, selected);
+                    }
+                    BulkAction::Snooze(preset) => {
+                        self.snooze(&selected, preset.wake_time(chrono::Local::now()));
+                    }
+                    BulkAction::Open => {
+                        return Task::batch(
+                            self.notifications
+                                .iter()
+                                .filter(|n| selected.contains(&n.id))
+                                .filter_map(Target::for_notification)
+                                .map(|target| self.open_target(target)),
+                        );
+                    }
+                }
+            }
+            Message::BulkItemFinished(kind, notification_id, result) => {
+                match result {
+                    Ok(()) if kind == PendingKind::Unsubscribe => {
+                        self.subscriptions
+                            .insert(notification_id, SubscriptionState::Ignored);
+                    }
+                    Ok(()) => {}
+                    Err(error) => {
+                        if let Some(bulk) = &mut self.bulk {
+                            let title = bulk
+                                .titles
+                                .get(&notification_id)
+                                .cloned()
+                                .unwrap_or_else(|| notification_id.to_string());
+                            bulk.failures.push((title, error));
+                        }
+                        if !kind.removes_threads() {
+                            let mut overrides = self.state.read_overrides.clone();
+                            overrides.remove(&notification_id);
+                            self.save_read_overrides(overrides);
+                        }
+                    }
+                }
+
+                if let Some(bulk) = &mut self.bulk {
+                    bulk.completed += 1;
+                    if bulk.is_finished() {
+                        if bulk.failures.is_empty() {
+                            self.bulk = None;
+                        }
+                        tasks.push(cosmic::task::message(Message::RefreshNotifications));
+                    }
+                }
+            }
+            Message::DismissBulkReport => {
+                self.bulk = None;
+            }
+            Message::DismissActionError => {
+                self.action_error = None;
+            }
+            Message::Undo => {
+                self.undo_pending();
+            }
+            Message::UndoFromNotification(expires) => {
+                // Ignore clicks on notifications of actions that were already sent or replaced.
+                if self
+                    .pending
+                    .as_ref()
+                    .is_some_and(|pending| pending.expires == expires)
+                {
+                    self.undo_pending();
+                }
+            }
+            Message::UndoTick => {
+                if self
+                    .pending
+                    .as_ref()
+                    .is_some_and(|pending| Instant::now() >= pending.expires)
+                {
+                    return self.commit_pending();
+                }
+            }
             Message::ToggleShowAll(show_all) => {
                 self.show_all = show_all;
                 tasks.push(cosmic::task::message(Message::RefreshNotifications));
@@ -442,40 +1256,1317 @@ impl cosmic::Application for AppModel {
     }
 }
 
-impl AppModel {
-    fn notification_item<'a>(&self, notification: &'a Notification) -> Element<'a, Message> {
-        let reason = format_reason(&notification.reason);
+/// Kinds of destructive actions that can be undone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingKind {
+    Read,
+    MarkAllRead,
+    /// Every thread of one repository; all affected threads share that repository.
+    MarkRepoRead,
+    Done,
+    Unsubscribe,
+}
 
-        let header = widget::row()
-            .push_maybe(
-                notification.unread.then_some(
-                    widget::button::icon(cosmic::widget::icon::from_name(
-                        "mail-mark-read-symbolic",
-                    ))
+impl PendingKind {
+    /// Whether the action takes threads out of the inbox.
+    fn removes_threads(self) -> bool {
+        matches!(self, PendingKind::Done | PendingKind::Unsubscribe)
+    }
+}
+
+/// Actions that can be applied to every selected thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    MarkRead,
+    Done,
+    Unsubscribe,
+    Snooze(SnoozePreset),
+    Open,
+}
+
+/// Progress of bulk requests sent to GitHub.
+#[derive(Debug, Default)]
+struct BulkProgress {
+    total: usize,
+    completed: usize,
+    /// Titles of the affected threads, used in the failure report.
+    titles: HashMap<NotificationId, String>,
+    /// Title and error of every request that failed.
+    failures: Vec<(String, String)>,
+}
+
+impl BulkProgress {
+    fn is_finished(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+/// A destructive action that has been applied locally but not yet sent to GitHub.
+struct PendingAction {
+    kind: PendingKind,
+    /// The affected threads as they were before the action.
+    notifications: Vec<Notification>,
+    /// Read overrides before the action, restored on undo.
+    previous_overrides: HashMap<NotificationId, ReadOverride>,
+    /// Fetch time of the list the action was taken on.
+    last_read_at: Option<chrono::DateTime<chrono::Utc>>,
+    expires: Instant,
+}
+
+/// Pages that can be shown in the popup.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    #[default]
+    Inbox,
+    Saved,
+    Watching,
+    Settings,
+}
+
+/// What the panel icon should convey, from most to least urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PanelState {
+    Error,
+    Offline,
+    SecurityAlert,
+    ReviewRequested,
+    Unread,
+    Read,
+}
+
+impl PanelState {
+    fn icon_name(self) -> &'static str {
+        match self {
+            PanelState::Error => "dialog-error-symbolic",
+            PanelState::Offline => "network-offline-symbolic",
+            PanelState::SecurityAlert => "security-low-symbolic",
+            PanelState::ReviewRequested => "mail-mark-important-symbolic",
+            PanelState::Unread => "mail-unread-symbolic",
+            PanelState::Read => "mail-read-symbolic",
+        }
+    }
+}
+
+impl AppModel {
+    fn open_popup(&mut self) -> Task<cosmic::Action<Message>> {
+        let new_id = Id::unique();
+        self.popup.replace(new_id);
+        let mut popup_settings = self.core.applet.get_popup_settings(
+            self.core.main_window_id().unwrap(),
+            new_id,
+            None,
+            None,
+            None,
+        );
+        popup_settings.positioner.size_limits = Limits::NONE
+            .max_width(1000.0)
+            .min_width(1000.0)
+            .min_height(1000.0)
+            .max_height(1000.0);
+        get_popup(popup_settings)
+    }
+
+    /// Notifications that aren't currently snoozed.
+    fn visible_notifications(&self) -> impl Iterator<Item = &Notification> {
+        let now = chrono::Utc::now();
+        self.notifications.iter().filter(move |n| {
+            !self
+                .state
+                .snoozed
+                .get(&n.id)
+                .is_some_and(|snooze| snooze.is_active(now))
+        })
+    }
+
+    /// Visible notifications grouped by repository, in the order the inbox draws them:
+    /// repositories in the order they first appear, threads by recency within each.
+    fn grouped_notifications(&self) -> Vec<(Option<&String>, Vec<&Notification>)> {
+        let mut groups: Vec<(Option<&String>, Vec<&Notification>)> = Vec::new();
+        for notification in self.visible_notifications() {
+            let repository = notification.repository.full_name.as_ref();
+            match groups.iter_mut().find(|(name, _)| *name == repository) {
+                Some((_, items)) => items.push(notification),
+                None => groups.push((repository, vec![notification])),
+            }
+        }
+        groups
+    }
+
+    fn refresh_unread_count(&mut self) {
+        self.unread_count = self.visible_notifications().filter(|n| n.unread).count();
+    }
+
+    /// Opens a link with the configured opener command.
+    fn open_target(&self, target: Target) -> Task<cosmic::Action<Message>> {
+        let command = self.config.opener_command.clone();
+        Task::future(async move {
+            if let Err(error) = opener::open(command, target).await {
+                eprintln!("failed to open link: {error}");
+            }
+        })
+        .discard()
+    }
+
+    fn save_saved(&mut self, saved: Vec<SavedThread>) {
+        match &self.state_handler {
+            Some(handler) => {
+                if let Err(error) = self.state.set_saved(handler, saved) {
+                    eprintln!("failed to save saved threads: {error}");
+                }
+            }
+            None => self.state.saved = saved,
+        }
+    }
+
+    fn save_snoozed(&mut self, snoozed: HashMap<NotificationId, Snooze>) {
+        match &self.state_handler {
+            Some(handler) => {
+                if let Err(error) = self.state.set_snoozed(handler, snoozed) {
+                    eprintln!("failed to save snoozed threads: {error}");
+                }
+            }
+            None => self.state.snoozed = snoozed,
+        }
+    }
+
+    /// Applies a destructive action locally and holds back the API call until the undo
+    /// window expires. A previously pending action is sent right away.
+    fn begin_pending(
+        &mut self,
+        kind: PendingKind,
+        notification_ids: Vec<NotificationId>,
+    ) -> Task<cosmic::Action<Message>> {
+        let commit = self.commit_pending();
+
+        let notifications: Vec<Notification> = self
+            .notifications
+            .iter()
+            .filter(|n| notification_ids.contains(&n.id))
+            .cloned()
+            .collect();
+        if notifications.is_empty() {
+            return commit;
+        }
+        let previous_overrides = self.state.read_overrides.clone();
+
+        if kind.removes_threads() {
+            self.notifications
+                .retain(|n| !notification_ids.contains(&n.id));
+            self.refresh_unread_count();
+        } else {
+            self.override_read_state(notification_ids, false);
+        }
+
+        let pending = PendingAction {
+            kind,
+            notifications,
+            previous_overrides,
+            last_read_at: self.fetched_at,
+            expires: Instant::now() + UNDO_TIMEOUT,
+        };
+
+        // Without the popup there's no undo bar, e.g. after "Mark all read" from the panel.
+        let prompt = if self.popup.is_none() {
+            let expires = pending.expires;
+            Task::perform(undo_prompt(pending_label(&pending)), move |undo| {
+                if undo {
+                    cosmic::Action::App(Message::UndoFromNotification(expires))
+                } else {
+                    cosmic::Action::None
+                }
+            })
+        } else {
+            Task::none()
+        };
+
+        self.pending = Some(pending);
+        Task::batch([commit, prompt])
+    }
+
+    /// Sends the pending action to GitHub.
+    fn commit_pending(&mut self) -> Task<cosmic::Action<Message>> {
+        let (Some(pending), Some(client)) = (self.pending.take(), &self.client) else {
+            return Task::none();
+        };
+
+        // Only mark what was on screen: anything that arrived after the fetch stays unread.
+        let last_read_at = pending.last_read_at;
+        if pending.kind == PendingKind::MarkAllRead {
+            let client = client.clone();
+            return Task::perform(
+                async move {
+                    client
+                        .activity()
+                        .notifications()
+                        .mark_all_as_read(last_read_at)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| cosmic::Action::App(Message::NotificationMarkedAsRead(None, result)),
+            );
+        }
+        if pending.kind == PendingKind::MarkRepoRead {
+            let client = client.clone();
+            let repository = pending
+                .notifications
+                .first()
+                .and_then(|n| n.repository.full_name.clone())
+                .unwrap_or_default();
+            return Task::perform(
+                async move {
+                    mark_repo_as_read(&client, &repository, last_read_at)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| cosmic::Action::App(Message::NotificationMarkedAsRead(None, result)),
+            );
+        }
+
+        let kind = pending.kind;
+        if let [notification] = pending.notifications.as_slice() {
+            let id = notification.id;
+            return Task::perform(send_thread_action(client.clone(), kind, id), move |result| {
+                cosmic::Action::App(match kind {
+                    PendingKind::Read | PendingKind::MarkAllRead | PendingKind::MarkRepoRead => {
+                        Message::NotificationMarkedAsRead(Some(id), result)
+                    }
+                    PendingKind::Done => Message::NotificationDone(result.map(|()| id)),
+                    PendingKind::Unsubscribe => Message::Unsubscribed(result.map(|()| id)),
+                })
+            });
+        }
+
+        // Several threads at once: keep the number of requests in flight bounded and
+        // report progress and failures per item.
+        let bulk = self.bulk.get_or_insert_with(BulkProgress::default);
+        bulk.total += pending.notifications.len();
+        for notification in &pending.notifications {
+            bulk.titles
+                .insert(notification.id, notification.subject.title.clone());
+        }
+
+        let permits = Arc::new(Semaphore::new(BULK_CONCURRENCY));
+        Task::batch(pending.notifications.iter().map(|notification| {
+            let client = client.clone();
+            let permits = permits.clone();
+            let id = notification.id;
+            Task::perform(
+                async move {
+                    let _permit = permits.acquire().await;
+                    send_thread_action(client, kind, id).await
+                },
+                move |result| cosmic::Action::App(Message::BulkItemFinished(kind, id, result)),
+            )
+        }))
+    }
+
+    /// Restores the local state from before the pending action.
+    fn undo_pending(&mut self) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+
+        self.save_read_overrides(pending.previous_overrides);
+        if pending.kind.removes_threads() {
+            for notification in pending.notifications {
+                if !self.notifications.iter().any(|n| n.id == notification.id) {
+                    self.notifications.push(notification);
+                }
+            }
+            self.notifications
+                .sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        } else {
+            for snapshot in pending.notifications {
+                if let Some(notification) =
+                    self.notifications.iter_mut().find(|n| n.id == snapshot.id)
+                {
+                    notification.unread = snapshot.unread;
+                }
+            }
+        }
+        self.refresh_unread_count();
+    }
+
+    fn selection_bar(&self) -> Element<'_, Message> {
+        let has_selection = !self.selected.is_empty();
+        let bulk_button = |icon: &'static str, tooltip: String, action: BulkAction| {
+            widget::button::icon(widget::icon::from_name(icon))
+                .tooltip(tooltip)
+                .padding(spacing().space_xxxs)
+                .on_press_maybe(has_selection.then_some(Message::Bulk(action)))
+        };
+
+        let actions = widget::row()
+            .push(
+                widget::text(fl!("selected-count", count = self.selected.len()))
+                    .size(spacing().space_xs),
+            )
+            .push(widget::horizontal_space().width(Length::Fill))
+            .push(
+                widget::button::icon(widget::icon::from_name("edit-select-all-symbolic"))
+                    .tooltip(fl!("select-all-visible"))
                     .padding(spacing().space_xxxs)
-                    .on_press(Message::MarkAsRead(notification.id.clone()))
-                    .class(cosmic::theme::Button::Text),
-                ),
+                    .on_press(Message::SelectAllVisible),
+            )
+            .push(bulk_button(
+                "mail-mark-read-symbolic",
+                fl!("mark-as-read"),
+                BulkAction::MarkRead,
+            ))
+            .push(bulk_button("object-select-symbolic", fl!("done"), BulkAction::Done))
+            .push(bulk_button(
+                "notifications-disabled-symbolic",
+                fl!("unsubscribe"),
+                BulkAction::Unsubscribe,
+            ))
+            .push(bulk_button(
+                "web-browser-symbolic",
+                fl!("open-in-browser"),
+                BulkAction::Open,
+            ))
+            .spacing(spacing().space_xxxs)
+            .align_y(Alignment::Center);
+
+        let mut snooze = widget::row()
+            .push(widget::text(fl!("snooze")).size(spacing().space_xs))
+            .spacing(spacing().space_xxs)
+            .align_y(Alignment::Center);
+        for preset in SnoozePreset::ALL {
+            let message = Message::Bulk(BulkAction::Snooze(preset));
+            snooze = snooze.push(
+                widget::button::text(snooze_preset_label(preset))
+                    .on_press_maybe(has_selection.then_some(message)),
+            );
+        }
+
+        widget::column()
+            .push(actions)
+            .push(snooze)
+            .spacing(spacing().space_xxs)
+            .apply(widget::container)
+            .class(cosmic::style::Container::Card)
+            .padding(spacing().space_xxs)
+            .into()
+    }
+
+    fn action_error_bar(&self) -> Option<Element<'_, Message>> {
+        let error = self.action_error.clone()?;
+
+        Some(
+            widget::row()
+                .push(inline_error(error))
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(
+                    widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                        .padding(spacing().space_xxxs)
+                        .on_press(Message::DismissActionError),
+                )
+                .align_y(Alignment::Center)
+                .apply(widget::container)
+                .class(cosmic::style::Container::Card)
+                .padding(spacing().space_xxs)
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+
+    fn bulk_report(&self) -> Option<Element<'_, Message>> {
+        let bulk = self.bulk.as_ref()?;
+
+        let mut report = widget::column().spacing(spacing().space_xxxs);
+        if bulk.is_finished() {
+            report = report.push(
+                widget::row()
+                    .push(
+                        widget::text(fl!("bulk-failed", count = bulk.failures.len()))
+                            .size(spacing().space_xs),
+                    )
+                    .push(widget::horizontal_space().width(Length::Fill))
+                    .push(
+                        widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                            .padding(spacing().space_xxxs)
+                            .on_press(Message::DismissBulkReport),
+                    )
+                    .align_y(Alignment::Center),
+            );
+        } else {
+            report = report
+                .push(
+                    widget::text(fl!(
+                        "bulk-progress",
+                        completed = bulk.completed,
+                        total = bulk.total
+                    ))
+                    .size(spacing().space_xs),
+                )
+                .push(widget::progress_bar(
+                    0.0..=bulk.total as f32,
+                    bulk.completed as f32,
+                ));
+        }
+
+        for (title, error) in &bulk.failures {
+            report = report.push(widget::text(format!("{title}: {error}")).size(11));
+        }
+
+        Some(
+            report
+                .apply(widget::container)
+                .class(cosmic::style::Container::Card)
+                .padding(spacing().space_xxs)
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+
+    fn undo_bar(&self) -> Option<Element<'_, Message>> {
+        let label = pending_label(self.pending.as_ref()?);
+
+        Some(
+            widget::row()
+                .push(widget::text(label).size(spacing().space_xs))
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(widget::button::text(fl!("undo")).on_press(Message::Undo))
+                .align_y(Alignment::Center)
+                .spacing(spacing().space_xxs)
+                .apply(widget::container)
+                .class(cosmic::style::Container::Card)
+                .padding(spacing().space_xxs)
+                .into(),
+        )
+    }
+
+    fn save_read_overrides(&mut self, overrides: HashMap<NotificationId, ReadOverride>) {
+        match &self.state_handler {
+            Some(handler) => {
+                if let Err(error) = self.state.set_read_overrides(handler, overrides) {
+                    eprintln!("failed to save read state: {error}");
+                }
+            }
+            None => self.state.read_overrides = overrides,
+        }
+    }
+
+    /// Pins the read state of the given threads locally, until they get new activity.
+    fn override_read_state(
+        &mut self,
+        notification_ids: impl IntoIterator<Item = NotificationId>,
+        unread: bool,
+    ) {
+        let mut overrides = self.state.read_overrides.clone();
+        for id in notification_ids {
+            if let Some(notification) = self.notifications.iter().find(|n| n.id == id) {
+                overrides.insert(
+                    id,
+                    ReadOverride {
+                        unread,
+                        updated_at: notification.updated_at,
+                    },
+                );
+            }
+        }
+        self.save_read_overrides(overrides);
+        self.reconcile_read_overrides(false);
+    }
+
+    /// Applies and prunes the read overrides, see [`state::reconcile_read_overrides`].
+    ///
+    /// Returns the threads kept unread locally that are missing from the list.
+    fn reconcile_read_overrides(&mut self, fetch_missing: bool) -> Vec<NotificationId> {
+        let mut overrides = self.state.read_overrides.clone();
+        let missing =
+            state::reconcile_read_overrides(&mut overrides, &mut self.notifications, fetch_missing);
+
+        if overrides != self.state.read_overrides {
+            self.save_read_overrides(overrides);
+        }
+        self.fetched_threads
+            .retain(|id, _| self.state.read_overrides.get(id).is_some_and(|o| o.unread));
+        self.refresh_unread_count();
+        missing
+    }
+
+    fn fetch_threads(
+        &self,
+        notification_ids: Vec<NotificationId>,
+    ) -> Task<cosmic::Action<Message>> {
+        let Some(client) = &self.client else {
+            return Task::none();
+        };
+        Task::batch(notification_ids.into_iter().map(|notification_id| {
+            let client = client.clone();
+            Task::perform(
+                async move {
+                    client
+                        .activity()
+                        .notifications()
+                        .get(notification_id)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| cosmic::Action::App(Message::ThreadFetched(result)),
+            )
+        }))
+    }
+
+    /// Fetches missing or outdated subject details for the visible notifications.
+    fn enrich_notifications(&mut self) -> Task<cosmic::Action<Message>> {
+        let Some(client) = &self.client else {
+            return Task::none();
+        };
+
+        let now = chrono::Utc::now();
+        let stale: Vec<(String, Notification)> = self
+            .visible_notifications()
+            .filter(|n| enrichment::is_enrichable(n))
+            .filter_map(|n| Some((enrichment::cache_key(n)?, n)))
+            .filter(|(key, n)| {
+                !self.enriching.contains(key)
+                    && self
+                        .enrichments
+                        .get(key)
+                        .is_none_or(|e| e.needs_refresh(n, now))
+            })
+            .map(|(key, n)| (key, n.clone()))
+            .collect();
+
+        // Issues and pull requests are batched through GraphQL while its budget lasts.
+        let (batchable, stale): (Vec<_>, Vec<_>) = stale
+            .into_iter()
+            .partition(|(_, n)| !self.graphql_unavailable && enrichment::is_batchable(n));
+        let budget_left = self.rate_limit.is_none_or(|limit| {
+            limit.remaining >= RATE_LIMIT_RESERVE || limit.reset_at <= chrono::Utc::now()
+        });
+        let batchable = if budget_left { batchable } else { Vec::new() };
+
+        let permits = Arc::new(Semaphore::new(ENRICHMENT_CONCURRENCY));
+        let mut tasks: Vec<_> = batchable
+            .chunks(ENRICHMENT_BATCH_SIZE)
+            .map(|chunk| {
+                let notifications: Vec<_> = chunk.iter().map(|(_, n)| n.clone()).collect();
+                for (key, _) in chunk {
+                    self.enriching.insert(key.clone());
+                }
+                let client = client.clone();
+                let viewer = self.viewer.clone();
+                let permits = permits.clone();
+                Task::perform(
+                    {
+                        let notifications = notifications.clone();
+                        async move {
+                            let _permit = permits.acquire().await;
+                            enrichment::fetch_batch(client, viewer, notifications).await
+                        }
+                    },
+                    move |result| {
+                        cosmic::Action::App(Message::EnrichedBatch(notifications, result))
+                    },
+                )
+            })
+            .collect();
+
+        tasks.extend(stale.into_iter().map(|(key, notification)| {
+            self.enriching.insert(key.clone());
+            let client = client.clone();
+            let viewer = self.viewer.clone();
+            let responses = self.responses.clone();
+            let permits = permits.clone();
+            Task::perform(
+                async move {
+                    let _permit = permits.acquire().await;
+                    enrichment::fetch(client, viewer, responses, notification)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                move |result| cosmic::Action::App(Message::Enriched(key, result)),
+            )
+        }));
+        Task::batch(tasks)
+    }
+
+    /// Writes the enrichment cache once no fetches are left in flight.
+    fn save_enrichments(&self) -> Task<cosmic::Action<Message>> {
+        if !self.enriching.is_empty() {
+            return Task::none();
+        }
+        Task::future(cache::save(self.enrichments.clone(), self.responses.clone())).discard()
+    }
+
+    /// Loads the repository owner and author avatars of the visible notifications.
+    fn load_avatars(&mut self) -> Task<cosmic::Action<Message>> {
+        let urls: Vec<String> = self
+            .visible_notifications()
+            .flat_map(|n| {
+                let enrichment = self.enrichment(n);
+                [
+                    n.repository
+                        .owner
+                        .as_ref()
+                        .map(|owner| owner.avatar_url.to_string()),
+                    enrichment.and_then(|e| e.author_avatar_url.clone()),
+                    enrichment
+                        .and_then(|e| e.comment.as_ref())
+                        .and_then(|comment| comment.avatar_url.clone()),
+                ]
+            })
+            .flatten()
+            .map(|url| avatar::sized_url(&url))
+            .collect();
+
+        let tasks: Vec<_> = urls
+            .into_iter()
+            .filter(|url| self.requested_avatars.insert(url.clone()))
+            .map(|url| {
+                Task::perform(
+                    {
+                        let url = url.clone();
+                        async move { avatar::fetch(url).await.map_err(|e| e.to_string()) }
+                    },
+                    move |result| cosmic::Action::App(Message::AvatarLoaded(url, result)),
+                )
+            })
+            .collect();
+        Task::batch(tasks)
+    }
+
+    /// An avatar image, once it has been downloaded.
+    fn avatar(&self, avatar_url: &str) -> Option<Element<'static, Message>> {
+        let handle = self.avatars.get(&avatar::sized_url(avatar_url))?;
+        Some(
+            widget::image(handle.clone())
+                .width(Length::Fixed(avatar::SIZE.into()))
+                .height(Length::Fixed(avatar::SIZE.into()))
+                .into(),
+        )
+    }
+
+    /// Author and first lines of the comment that last updated a thread.
+    fn comment_preview<'a>(&self, comment: &Comment) -> Element<'a, Message> {
+        widget::row()
+            .push_maybe(
+                comment
+                    .avatar_url
+                    .as_deref()
+                    .and_then(|url| self.avatar(url)),
             )
             .push(
                 widget::column()
                     .push(
-                        widget::button::link(notification.subject.title.clone())
-                            .padding(spacing().space_none)
-                            .on_press(Message::OpenNotification(notification.clone())),
+                        widget::text(comment.author.clone())
+                            .size(spacing().space_xs)
+                            .font(cosmic::font::bold()),
                     )
-                    .push_maybe(
-                        notification
-                            .repository
-                            .full_name
-                            .as_ref()
-                            .map(|name| widget::text(name).size(spacing().space_xs)),
+                    .push(widget::text(comment.snippet.clone()).size(spacing().space_xs))
+                    .spacing(spacing().space_xxxs),
+            )
+            .spacing(spacing().space_xxs)
+            .into()
+    }
+
+    /// Labels of a notification's subject that pass the allowlist.
+    fn label_chips<'a>(&self, notification: &Notification) -> Option<Element<'a, Message>> {
+        let chips: Vec<_> = self
+            .enrichment(notification)?
+            .labels
+            .iter()
+            .filter(|label| self.config.shows_label(&label.name))
+            .map(label_chip)
+            .collect();
+        if chips.is_empty() {
+            return None;
+        }
+
+        Some(
+            widget::flex_row(chips)
+                .row_spacing(spacing().space_xxxs)
+                .column_spacing(spacing().space_xxxs)
+                .into(),
+        )
+    }
+
+    /// Subject details of a notification, if they are known and current.
+    fn enrichment(&self, notification: &Notification) -> Option<&Enrichment> {
+        self.enrichments
+            .get(&enrichment::cache_key(notification)?)
+            .filter(|e| e.is_fresh_for(notification))
+    }
+
+    fn snooze(
+        &mut self,
+        notification_ids: &[NotificationId],
+        until: chrono::DateTime<chrono::Utc>,
+    ) {
+        let mut snoozed = self.state.snoozed.clone();
+        for notification in &self.notifications {
+            if notification_ids.contains(&notification.id) {
+                snoozed.insert(
+                    notification.id,
+                    Snooze {
+                        until,
+                        title: notification.subject.title.clone(),
+                        repository: notification.repository.full_name.clone(),
+                        updated_at: notification.updated_at,
+                    },
+                );
+            }
+        }
+        self.save_snoozed(snoozed);
+        self.item_menu = None;
+        self.refresh_unread_count();
+    }
+
+    /// Resurfaces snoozed threads whose wake time has passed.
+    fn wake_snoozed(&mut self) -> Task<cosmic::Action<Message>> {
+        let now = chrono::Utc::now();
+        let (woken, snoozed): (HashMap<_, _>, HashMap<_, _>) = self
+            .state
+            .snoozed
+            .clone()
+            .into_iter()
+            .partition(|(_, snooze)| !snooze.is_active(now));
+
+        if woken.is_empty() {
+            return Task::none();
+        }
+        self.save_snoozed(snoozed);
+
+        // Woken threads come back as new, even if they were read on GitHub meanwhile.
+        let mut overrides = self.state.read_overrides.clone();
+        for (notification_id, snooze) in &woken {
+            overrides.insert(
+                *notification_id,
+                ReadOverride {
+                    unread: true,
+                    updated_at: snooze.updated_at,
+                },
+            );
+        }
+        self.save_read_overrides(overrides);
+
+        let missing = self.reconcile_read_overrides(true);
+        let mut tasks = vec![self.fetch_threads(missing)];
+        for snooze in woken.into_values() {
+            tasks.push(Task::future(snooze::announce(snooze)).discard());
+        }
+        Task::batch(tasks)
+    }
+
+    fn is_polling_paused(&self) -> bool {
+        self.polling_paused_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn context_menu_view(&self) -> Element<'_, Message> {
+        let paused = self.is_polling_paused();
+        let mut menu = widget::column().padding([spacing().space_xxs, spacing().space_none]);
+        for action in PanelAction::ALL {
+            let enabled = match action {
+                PanelAction::Refresh => self.client.is_some() && !self.is_loading,
+                PanelAction::MarkAllRead => !self.notifications.is_empty(),
+                _ => true,
+            };
+            menu = menu.push(
+                cosmic::applet::menu_button(widget::text(panel_action_label(action, paused)))
+                    .on_press_maybe(enabled.then_some(Message::PanelAction(action))),
+            );
+        }
+
+        self.core.applet.popup_container(menu).into()
+    }
+
+    /// Header for secondary pages, with a button leading back to the inbox.
+    fn page_header(&self, title: String) -> widget::Row<'_, Message> {
+        widget::row()
+            .push(
+                widget::button::icon(widget::icon::from_name("go-previous-symbolic"))
+                    .padding(spacing().space_xxs)
+                    .on_press(Message::ShowPage(Page::Inbox)),
+            )
+            .push(widget::text(title).size(spacing().space_s))
+            .spacing(spacing().space_xxs)
+            .align_y(Alignment::Center)
+    }
+
+    fn settings_view(&self) -> widget::Column<'_, Message> {
+        let header = self.page_header(fl!("settings"));
+
+        let middle_click = PanelAction::ALL
+            .iter()
+            .position(|action| *action == self.config.middle_click_action);
+
+        let section = widget::settings::section()
+            .add(widget::settings::item(
+                fl!("middle-click-action"),
+                widget::dropdown(
+                    self.panel_action_labels.as_slice(),
+                    middle_click,
+                    Message::SetMiddleClickAction,
+                ),
+            ))
+            .add(
+                widget::column()
+                    .push(widget::text(fl!("opener-command")))
+                    .push(
+                        widget::text_input(
+                            fl!("opener-command-placeholder"),
+                            &self.config.opener_command,
+                        )
+                        .on_input(Message::SetOpenerCommand),
+                    )
+                    .push(widget::text(fl!("opener-command-help")).size(spacing().space_xs))
+                    .spacing(spacing().space_xxs),
+            )
+            .add(
+                widget::column()
+                    .push(widget::text(fl!("label-allowlist")))
+                    .push(
+                        widget::text_input(
+                            fl!("label-allowlist-placeholder"),
+                            &self.label_allowlist_input,
+                        )
+                        .on_input(Message::SetLabelAllowlist),
+                    )
+                    .push(widget::text(fl!("label-allowlist-help")).size(spacing().space_xs))
+                    .spacing(spacing().space_xxs),
+            );
+
+        widget::column()
+            .push(header)
+            .push(section)
+            .spacing(spacing().space_xs)
+    }
+
+    fn saved_view(&self) -> widget::Column<'_, Message> {
+        let header = self.page_header(fl!("saved"));
+
+        if self.state.saved.is_empty() {
+            return widget::column()
+                .push(header)
+                .push(
+                    widget::container(
+                        widget::column()
+                            .push(widget::text(fl!("no-saved")).size(spacing().space_s))
+                            .push(widget::text(fl!("no-saved-desc")).size(spacing().space_xs))
+                            .spacing(spacing().space_xxs)
+                            .align_x(Alignment::Center)
+                            .width(Length::Fill),
+                    )
+                    .padding(spacing().space_l)
+                    .class(cosmic::theme::Container::Card),
+                )
+                .spacing(spacing().space_xs);
+        }
+
+        let mut list = widget::column().spacing(spacing().space_xxxs);
+        for saved in &self.state.saved {
+            let title = widget::column()
+                .push(
+                    widget::button::link(saved.title.clone())
+                        .padding(spacing().space_none)
+                        .on_press_maybe(saved.url.as_ref().map(|url| {
+                            Message::OpenUrl(Target::new(url.clone(), saved.repository.clone()))
+                        })),
+                )
+                .push_maybe(
+                    saved
+                        .repository
+                        .as_ref()
+                        .map(|name| widget::text(name).size(spacing().space_xs)),
+                )
+                .spacing(spacing().space_xxxs);
+
+            let row = widget::row()
+                .push(title)
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(
+                    widget::button::icon(widget::icon::from_name("starred-symbolic"))
+                        .tooltip(fl!("unsave"))
+                        .padding(spacing().space_xxxs)
+                        .on_press(Message::ToggleSaved(saved.id))
+                        .class(cosmic::theme::Button::Text),
+                )
+                .spacing(spacing().space_xxs)
+                .align_y(Alignment::Center);
+
+            list = list.push(
+                widget::container(
+                    widget::column()
+                        .push(row)
+                        .push(widget::text(format_time_ago(&saved.saved_at)).size(11))
+                        .spacing(spacing().space_xxxs),
+                )
+                .class(cosmic::style::Container::Card)
+                .padding(spacing().space_xxs)
+                .width(Length::Fill),
+            );
+        }
+
+        widget::column()
+            .push(header)
+            .push(widget::scrollable(list).height(Length::Fixed(400.0)))
+            .spacing(spacing().space_xs)
+    }
+
+    fn watching_view(&self) -> widget::Column<'_, Message> {
+        let header = self
+            .page_header(fl!("watched-repositories"))
+            .push(widget::horizontal_space().width(Length::Fill))
+            .push(
+                widget::button::icon(widget::icon::from_name("view-refresh-symbolic"))
+                    .padding(spacing().space_xxs)
+                    .on_press_maybe(
+                        (!self.is_loading_watched).then_some(Message::LoadWatchedRepos),
+                    ),
+            );
+
+        if self.is_loading_watched && self.watched_repos.is_empty() {
+            return widget::column()
+                .push(header)
+                .push(
+                    widget::container(widget::text(fl!("loading-watched")).size(spacing().space_xs))
+                        .padding(spacing().space_s)
+                        .width(Length::Fill)
+                        .class(cosmic::theme::Container::Card),
+                )
+                .spacing(spacing().space_xs);
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for notification in &self.notifications {
+            if let Some(name) = &notification.repository.full_name {
+                *counts.entry(name.as_str()).or_default() += 1;
+            }
+        }
+
+        // Noisiest repositories first.
+        let mut repos: Vec<_> = self
+            .watched_repos
+            .iter()
+            .map(|repo| (repo, counts.get(repo.full_name.as_str()).copied().unwrap_or(0)))
+            .collect();
+        repos.sort_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.full_name.cmp(&b.full_name))
+        });
+
+        let mut list = widget::column().spacing(spacing().space_xxxs);
+        for (repo, count) in repos {
+            let level_button = |label: String, level: WatchLevel| {
+                widget::button::text(label)
+                    .class(if repo.level == Some(level) {
+                        cosmic::theme::Button::Suggested
+                    } else {
+                        cosmic::theme::Button::Standard
+                    })
+                    .on_press(Message::SetWatchLevel(repo.full_name.clone(), level))
+            };
+
+            let controls = widget::row()
+                .push(level_button(fl!("watch-all"), WatchLevel::All))
+                .push(level_button(fl!("watch-participating"), WatchLevel::Participating))
+                .push(level_button(fl!("watch-ignore"), WatchLevel::Ignored))
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(
+                    widget::button::destructive(fl!("unwatch"))
+                        .on_press(Message::Unwatch(repo.full_name.clone())),
+                )
+                .spacing(spacing().space_xxs);
+
+            let title = widget::row()
+                .push(widget::text(&repo.full_name))
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(
+                    widget::text(fl!("notifications-count", count = count))
+                        .size(spacing().space_xs),
+                )
+                .align_y(Alignment::Center);
+
+            let mut card = widget::column()
+                .push(title)
+                .push(controls)
+                .spacing(spacing().space_xxs);
+            if self.failed_watch_levels.contains(&repo.full_name) {
+                card = card.push(inline_error(fl!("error-watch-level")));
+            } else if repo.level.is_none() {
+                card = card.push(inline_error(fl!("error-watch-level-unknown")));
+            }
+
+            list = list.push(
+                widget::container(card)
+                .class(cosmic::style::Container::Card)
+                .padding(spacing().space_xxs)
+                .width(Length::Fill),
+            );
+        }
+
+        let mut column = widget::column().push(header);
+        if let Some(error) = &self.watched_error {
+            column = column.push(inline_error(error.clone()));
+        }
+        column
+            .push(widget::scrollable(list).height(Length::Fixed(400.0)))
+            .spacing(spacing().space_xs)
+    }
+
+    fn panel_button_with_count(&self, icon_name: &'static str) -> widget::Button<'_, Message> {
+        let icon = widget::icon::from_name(icon_name)
+            .size(self.core.applet.suggested_size(true).0)
+            .symbolic(true)
+            .icon();
+        let count = self.core.applet.text(format_unread_count(self.unread_count));
+
+        // Horizontal panels have room for the count beside the icon, vertical ones below it.
+        let content: Element<'_, Message> = if self.core.applet.is_horizontal() {
+            widget::row()
+                .push(icon)
+                .push(count)
+                .spacing(spacing().space_xxxs)
+                .align_y(Alignment::Center)
+                .into()
+        } else {
+            widget::column()
+                .push(icon)
+                .push(count)
+                .align_x(Alignment::Center)
+                .into()
+        };
+
+        self.core.applet.button_from_element(content, true)
+    }
+
+    fn tooltip_summary(&self) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(error) = &self.error_message {
+            parts.push(error.clone());
+        } else {
+            let unread_with_reason = |reasons: &[&str]| {
+                self.visible_notifications()
+                    .filter(|n| n.unread && reasons.contains(&n.reason.as_str()))
+                    .count()
+            };
+            let review_requests = unread_with_reason(&["review_requested"]);
+            let mentions = unread_with_reason(&["mention", "team_mention"]);
+
+            parts.push(fl!("tooltip-unread", count = self.unread_count));
+            if review_requests > 0 {
+                parts.push(fl!("tooltip-review-requests", count = review_requests));
+            }
+            if mentions > 0 {
+                parts.push(fl!("tooltip-mentions", count = mentions));
+            }
+        }
+
+        if let Some(last_refresh) = self.last_refresh {
+            let elapsed = chrono::Duration::from_std(last_refresh.elapsed())
+                .unwrap_or_else(|_| chrono::Duration::zero());
+            parts.push(fl!(
+                "tooltip-last-refreshed",
+                time = format_duration_ago(elapsed)
+            ));
+        }
+
+        parts.join(" · ")
+    }
+
+    fn panel_state(&self) -> PanelState {
+        match self.error_kind {
+            Some(ErrorKind::Offline) => return PanelState::Offline,
+            Some(ErrorKind::Auth | ErrorKind::Other) => return PanelState::Error,
+            None => {}
+        }
+
+        let unread = || self.visible_notifications().filter(|n| n.unread);
+        if unread().any(|n| {
+            n.reason == "security_alert" || n.subject.r#type == "RepositoryVulnerabilityAlert"
+        }) {
+            PanelState::SecurityAlert
+        } else if unread().any(|n| n.reason == "review_requested") {
+            PanelState::ReviewRequested
+        } else if self.unread_count > 0 {
+            PanelState::Unread
+        } else {
+            PanelState::Read
+        }
+    }
+
+    fn notification_item<'a>(&self, notification: &'a Notification) -> Element<'a, Message> {
+        let review = self.enrichment(notification).and_then(|e| e.review);
+        let reason = match review {
+            Some(review) => review_reason(review),
+            None => format_reason(&notification.reason),
+        };
+
+        let (read_icon, read_message) = if notification.unread {
+            ("mail-mark-read-symbolic", Message::MarkAsRead(notification.id))
+        } else {
+            ("mail-mark-unread-symbolic", Message::MarkAsUnread(notification.id))
+        };
+
+        let id = notification.id;
+        let is_saved = self.state.saved.iter().any(|saved| saved.id == id);
+        let header = widget::row()
+            .push_maybe(self.selection_mode.then(|| {
+                widget::checkbox("", self.selected.contains(&id))
+                    .on_toggle(move |checked| Message::ToggleSelected(id, checked))
+            }))
+            .push(
+                widget::button::icon(widget::icon::from_name(read_icon))
+                    .padding(spacing().space_xxxs)
+                    .on_press(read_message)
+                    .class(cosmic::theme::Button::Text),
+            )
+            .push_maybe(
+                notification
+                    .repository
+                    .owner
+                    .as_ref()
+                    .and_then(|owner| self.avatar(owner.avatar_url.as_str())),
+            )
+            .push(
+                widget::icon::from_name(subject_type_icon(&notification.subject.r#type))
+                    .size(16),
+            )
+            .push(
+                widget::column()
+                    .push(
+                        widget::row()
+                            .push_maybe(
+                                self.enrichment(notification)
+                                    .and_then(|e| e.author_avatar_url.as_deref())
+                                    .and_then(|url| self.avatar(url)),
+                            )
+                            .push_maybe(
+                                self.enrichment(notification)
+                                    .and_then(|e| e.pull_state)
+                                    .map(pull_state_badge),
+                            )
+                            .push(
+                                widget::button::link(notification.subject.title.clone())
+                                    .padding(spacing().space_none)
+                                    .on_press(Message::OpenNotification(notification.clone())),
+                            )
+                            .push_maybe(
+                                self.enrichment(notification)
+                                    .and_then(|e| e.checks.as_ref())
+                                    .map(|checks| checks_badge(checks, notification)),
+                            )
+                            .spacing(spacing().space_xxs)
+                            .align_y(Alignment::Center),
                     )
+                    .push(widget::text(subject_line(notification)).size(spacing().space_xs))
+                    .push_maybe(self.label_chips(notification))
                     .spacing(spacing().space_xxxs),
             )
+            .push(widget::horizontal_space().width(Length::Fill))
+            .push(
+                widget::button::icon(widget::icon::from_name("object-select-symbolic"))
+                    .tooltip(fl!("done"))
+                    .padding(spacing().space_xxxs)
+                    .on_press(Message::MarkAsDone(notification.id))
+                    .class(cosmic::theme::Button::Text),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name(if is_saved {
+                    "starred-symbolic"
+                } else {
+                    "non-starred-symbolic"
+                }))
+                .tooltip(if is_saved { fl!("unsave") } else { fl!("save") })
+                .padding(spacing().space_xxxs)
+                .on_press(Message::ToggleSaved(notification.id))
+                .class(cosmic::theme::Button::Text),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("view-more-symbolic"))
+                    .padding(spacing().space_xxxs)
+                    .on_press(Message::ToggleItemMenu(notification.id))
+                    .class(cosmic::theme::Button::Text),
+            )
             .spacing(spacing().space_xxs)
             .align_y(Alignment::Center);
 
+        let menu = (self.item_menu == Some(notification.id)).then(|| {
+            let state = self.subscriptions.get(&notification.id);
+            let subscription: Element<'_, Message> = match state {
+                Some(SubscriptionState::Subscribed) => {
+                    widget::text(fl!("subscription-subscribed")).size(spacing().space_xs).into()
+                }
+                Some(SubscriptionState::Ignored) => {
+                    widget::text(fl!("subscription-ignored")).size(spacing().space_xs).into()
+                }
+                Some(SubscriptionState::NotSubscribed) => {
+                    widget::text(fl!("subscription-none")).size(spacing().space_xs).into()
+                }
+                None if self.failed_subscriptions.contains(&notification.id) => {
+                    inline_error(fl!("error-subscription"))
+                }
+                None => widget::text(fl!("subscription-loading")).size(spacing().space_xs).into(),
+            };
+
+            let subscription = widget::row()
+                .push(subscription)
+                .push(widget::horizontal_space().width(Length::Fill))
+                .push(
+                    widget::button::text(fl!("unsubscribe"))
+                        .on_press(Message::Unsubscribe(notification.id)),
+                )
+                .spacing(spacing().space_xxs)
+                .align_y(Alignment::Center);
+
+            let mut presets = widget::row()
+                .push(widget::text(fl!("snooze")).size(spacing().space_xs))
+                .spacing(spacing().space_xxs)
+                .align_y(Alignment::Center);
+            for preset in SnoozePreset::ALL {
+                presets = presets.push(
+                    widget::button::text(snooze_preset_label(preset))
+                        .on_press(Message::Snooze(notification.id, preset)),
+                );
+            }
+
+            let custom = widget::row()
+                .push(
+                    widget::text_input(fl!("snooze-custom-placeholder"), &self.snooze_input)
+                        .on_input(Message::SnoozeInput)
+                        .on_submit(move |_| Message::SnoozeCustom(notification.id)),
+                )
+                .push(
+                    widget::button::text(fl!("snooze"))
+                        .on_press(Message::SnoozeCustom(notification.id)),
+                )
+                .spacing(spacing().space_xxs)
+                .align_y(Alignment::Center);
+            let custom = widget::column()
+                .push(custom)
+                .push_maybe(
+                    self.snooze_input_invalid
+                        .then(|| inline_error(fl!("error-snooze-time"))),
+                )
+                .spacing(spacing().space_xxxs);
+
+            let mut copy = widget::row()
+                .push(widget::text(fl!("copy")).size(spacing().space_xs))
+                .spacing(spacing().space_xxs)
+                .align_y(Alignment::Center);
+            for (label, text) in [
+                (fl!("copy-link"), get_notification_url(notification)),
+                (fl!("copy-reference"), subject_full_reference(notification)),
+                (fl!("copy-markdown"), subject_markdown_link(notification)),
+            ] {
+                copy = copy.push(
+                    widget::button::text(label)
+                        .on_press_maybe(text.map(Message::CopyToClipboard)),
+                );
+            }
+
+            widget::column()
+                .push(subscription)
+                .push(copy)
+                .push(presets)
+                .push(custom)
+                .spacing(spacing().space_xxs)
+        });
+
         let time_ago = format_time_ago(&notification.updated_at);
 
         let footer = widget::row()
@@ -486,6 +2577,13 @@ impl AppModel {
         widget::container(
             widget::column()
                 .push(header)
+                .push_maybe(review.map(review_decision_text))
+                .push_maybe(
+                    self.enrichment(notification)
+                        .and_then(|e| e.comment.as_ref())
+                        .map(|comment| self.comment_preview(comment)),
+                )
+                .push_maybe(menu)
                 .push(footer)
                 .spacing(spacing().space_xxxs),
         )
@@ -496,17 +2594,287 @@ impl AppModel {
     }
 }
 
+fn pending_label(pending: &PendingAction) -> String {
+    let count = pending.notifications.len();
+    match pending.kind {
+        PendingKind::Read | PendingKind::MarkAllRead | PendingKind::MarkRepoRead => {
+            fl!("undo-read", count = count)
+        }
+        PendingKind::Done => fl!("undo-done", count = count),
+        PendingKind::Unsubscribe => fl!("undo-unsubscribe", count = count),
+    }
+}
+
+/// Shows a desktop notification with an Undo button for the length of the undo window.
+///
+/// Returns whether Undo was clicked.
+async fn undo_prompt(label: String) -> bool {
+    let timeout = notify_rust::Timeout::Milliseconds(UNDO_TIMEOUT.as_millis() as u32);
+    tokio::task::spawn_blocking(move || {
+        let handle = notify_rust::Notification::new()
+            .appname("Git Work")
+            .icon("dev.edfloreshz.GitWork")
+            .summary(&label)
+            .action("undo", &fl!("undo"))
+            .timeout(timeout)
+            .show()
+            .map_err(|error| eprintln!("failed to show the undo notification: {error}"))
+            .ok()?;
+
+        let mut undo = false;
+        handle.wait_for_action(|action| undo = action == "undo");
+        Some(undo)
+    })
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or_default()
+}
+
+/// Sends a single thread action to GitHub.
+async fn send_thread_action(
+    client: Octocrab,
+    kind: PendingKind,
+    id: NotificationId,
+) -> Result<(), String> {
+    match kind {
+        PendingKind::Read | PendingKind::MarkAllRead | PendingKind::MarkRepoRead => client
+            .activity()
+            .notifications()
+            .mark_as_read(id)
+            .await
+            .map_err(|e| e.to_string()),
+        PendingKind::Done => mark_thread_done(&client, id)
+            .await
+            .map_err(|e| e.to_string()),
+        PendingKind::Unsubscribe => {
+            ignore_thread(&client, id).await.map_err(|e| e.to_string())?;
+            mark_thread_done(&client, id)
+                .await
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn pull_state_badge<'a>(state: PullState) -> Element<'a, Message> {
+    // GitHub's own state colors.
+    let (label, color) = match state {
+        PullState::Open => (fl!("pr-open"), Color::from_rgb8(0x1a, 0x7f, 0x37)),
+        PullState::Draft => (fl!("pr-draft"), Color::from_rgb8(0x59, 0x63, 0x6e)),
+        PullState::Merged => (fl!("pr-merged"), Color::from_rgb8(0x82, 0x50, 0xdf)),
+        PullState::Closed => (fl!("pr-closed"), Color::from_rgb8(0xd1, 0x24, 0x2f)),
+    };
+
+    widget::text(format!("● {label}"))
+        .size(spacing().space_xs)
+        .class(cosmic::theme::Text::Color(color))
+        .into()
+}
+
+fn checks_badge<'a>(checks: &Checks, notification: &Notification) -> Element<'a, Message> {
+    let (label, color) = match checks.state {
+        CheckState::Passing => (fl!("checks-passing"), Color::from_rgb8(0x1a, 0x7f, 0x37)),
+        CheckState::Failing => (fl!("checks-failing"), Color::from_rgb8(0xd1, 0x24, 0x2f)),
+        CheckState::Pending => (fl!("checks-pending"), Color::from_rgb8(0x9a, 0x67, 0x00)),
+    };
+    let text = widget::text(label)
+        .size(spacing().space_xs)
+        .class(cosmic::theme::Text::Color(color));
+
+    // A failing badge leads straight to the failing run.
+    match &checks.failing_url {
+        Some(url) => widget::button::custom(text)
+            .padding(spacing().space_none)
+            .class(cosmic::theme::Button::Text)
+            .on_press(Message::OpenUrl(Target::new(
+                url.clone(),
+                notification.repository.full_name.clone(),
+            )))
+            .into(),
+        None => text.into(),
+    }
+}
+
+fn label_chip<'a>(label: &Label) -> Element<'a, Message> {
+    let color = u32::from_str_radix(&label.color, 16)
+        .ok()
+        .filter(|_| label.color.len() == 6)
+        .map(|rgb| Color::from_rgb8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+        .unwrap_or(Color::from_rgb8(0xed, 0xed, 0xed));
+
+    widget::container(widget::text(label.name.clone()).size(spacing().space_xs))
+        .padding([0, spacing().space_xxs])
+        .class(cosmic::theme::Container::custom(move |theme| {
+            let theme = theme.cosmic();
+            // Like GitHub's dark mode: a tinted chip with the label color lightened for the
+            // text, instead of a saturated chip that glares against the dark background.
+            let (background, text) = if theme.is_dark {
+                let text = mix(color, Color::WHITE, (0.6 - luminance(color)).max(0.0));
+                (Color { a: 0.2, ..color }, text)
+            } else if luminance(color) > 0.5 {
+                (color, Color::BLACK)
+            } else {
+                (color, Color::WHITE)
+            };
+
+            cosmic::iced::widget::container::Style {
+                background: Some(background.into()),
+                text_color: Some(text),
+                border: cosmic::iced::Border {
+                    radius: theme.corner_radii.radius_xl.into(),
+                    width: 1.0,
+                    color: Color { a: 0.4, ..text },
+                },
+                ..Default::default()
+            }
+        }))
+        .into()
+}
+
+/// Perceived brightness of a color, from 0 to 1.
+fn luminance(color: Color) -> f32 {
+    0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b
+}
+
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    Color::from_rgb(
+        from.r + (to.r - from.r) * amount,
+        from.g + (to.g - from.g) * amount,
+        from.b + (to.b - from.b) * amount,
+    )
+}
+
+/// An error shown next to the control it's about, instead of replacing the inbox.
+fn inline_error<'a>(message: String) -> Element<'a, Message> {
+    widget::text(message)
+        .size(spacing().space_xs)
+        .class(cosmic::theme::Text::Color(Color::from_rgb8(0xd1, 0x24, 0x2f)))
+        .into()
+}
+
+/// Replaces the plain "Review requested" reason once the request's progress is known.
+fn review_reason(review: Review) -> String {
+    if review.reviewed_by_viewer && review.new_commits_since_review {
+        fl!("review-new-commits")
+    } else if review.viewer_requested {
+        fl!("review-pending")
+    } else if review.reviewed_by_viewer {
+        fl!("review-done")
+    } else if review.team_requested {
+        // A team request can't be tied to the user without org access, so don't claim
+        // it's been satisfied.
+        format_reason("review_requested")
+    } else {
+        fl!("review-satisfied")
+    }
+}
+
+fn review_decision_text<'a>(review: Review) -> Element<'a, Message> {
+    let (label, color) = match review.decision {
+        ReviewDecision::Approved => (fl!("review-approved"), Color::from_rgb8(0x1a, 0x7f, 0x37)),
+        ReviewDecision::ChangesRequested => (
+            fl!("review-changes-requested"),
+            Color::from_rgb8(0xd1, 0x24, 0x2f),
+        ),
+        ReviewDecision::ReviewRequired => (
+            fl!("review-required"),
+            Color::from_rgb8(0x9a, 0x67, 0x00),
+        ),
+    };
+
+    widget::text(label)
+        .size(spacing().space_xs)
+        .class(cosmic::theme::Text::Color(color))
+        .into()
+}
+
+fn subject_type_icon(subject_type: &str) -> &'static str {
+    match subject_type {
+        "PullRequest" => "vcs-pull-request-symbolic",
+        "Issue" => "dialog-information-symbolic",
+        "Release" => "software-update-available-symbolic",
+        "Discussion" => "chat-message-new-symbolic",
+        "Commit" => "vcs-commit-symbolic",
+        "CheckSuite" => "system-run-symbolic",
+        "RepositoryVulnerabilityAlert" => "security-low-symbolic",
+        "RepositoryInvitation" => "contact-new-symbolic",
+        _ => "mail-unread-symbolic",
+    }
+}
+
+fn subject_type_label(subject_type: &str) -> Option<String> {
+    Some(match subject_type {
+        "PullRequest" => fl!("type-pull-request"),
+        "Issue" => fl!("type-issue"),
+        "Release" => fl!("type-release"),
+        "Discussion" => fl!("type-discussion"),
+        "Commit" => fl!("type-commit"),
+        "CheckSuite" => fl!("type-check-suite"),
+        "RepositoryVulnerabilityAlert" => fl!("type-security-alert"),
+        "RepositoryInvitation" => fl!("type-repository-invitation"),
+        _ => return None,
+    })
+}
+
+/// Secondary line of an item, e.g. "owner/repo · Pull Request #123".
+fn subject_line(notification: &Notification) -> String {
+    let subject = [
+        subject_type_label(&notification.subject.r#type),
+        subject_reference(notification),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    [notification.repository.full_name.clone(), Some(subject)]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn snooze_preset_label(preset: SnoozePreset) -> String {
+    match preset {
+        SnoozePreset::OneHour => fl!("snooze-one-hour"),
+        SnoozePreset::ThisEvening => fl!("snooze-this-evening"),
+        SnoozePreset::Tomorrow => fl!("snooze-tomorrow"),
+        SnoozePreset::NextWeek => fl!("snooze-next-week"),
+    }
+}
+
+fn panel_action_label(action: PanelAction, polling_paused: bool) -> String {
+    match action {
+        PanelAction::Refresh => fl!("refresh"),
+        PanelAction::MarkAllRead => fl!("mark-all-read"),
+        PanelAction::PausePolling if polling_paused => fl!("resume-polling"),
+        PanelAction::PausePolling => fl!("pause-polling"),
+        PanelAction::OpenInbox => fl!("open-inbox"),
+        PanelAction::Settings => fl!("settings"),
+    }
+}
+
+fn format_unread_count(count: usize) -> String {
+    if count > 99 {
+        "99+".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
 fn format_time_ago(datetime: &chrono::DateTime<chrono::Utc>) -> String {
-    let now = chrono::Utc::now();
-    let duration = now.signed_duration_since(*datetime);
+    format_duration_ago(chrono::Utc::now().signed_duration_since(*datetime))
+}
 
+fn format_duration_ago(duration: chrono::Duration) -> String {
     if duration.num_days() > 0 {
-        format!("{} days ago", duration.num_days())
+        fl!("time-days-ago", days = duration.num_days())
     } else if duration.num_hours() > 0 {
-        format!("{} hours ago", duration.num_hours())
+        fl!("time-hours-ago", hours = duration.num_hours())
     } else if duration.num_minutes() > 0 {
-        format!("{} minutes ago", duration.num_minutes())
+        fl!("time-minutes-ago", minutes = duration.num_minutes())
     } else {
-        "Just now".to_string()
+        fl!("time-just-now")
     }
 }
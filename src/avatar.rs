@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Avatar images of GitHub users and organizations, cached on disk.
+//!
+//! Avatars live in `$XDG_CACHE_HOME/git-work/avatars`, one file per sized URL plus its ETag.
+//! Cached files younger than [`MAX_AGE`] are used as is; older ones are revalidated.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use http::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+use http::StatusCode;
+use http_body_util::{BodyExt, Limited};
+use octocrab::Octocrab;
+use tokio::sync::OnceCell;
+
+use crate::cache;
+use crate::github::{Error, ErrorKind};
+
+/// Displayed size of avatars, in logical pixels.
+pub const SIZE: u16 = 20;
+/// How long a cached avatar is used before asking GitHub whether it changed.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Largest avatar that is downloaded; GitHub's are a few kilobytes at this size.
+const MAX_IMAGE_BYTES: usize = 512 * 1024;
+/// Size of the cache beyond which the least recently used avatars are evicted.
+const MAX_CACHE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Avatars are public, so they are downloaded without sending the token along.
+static CLIENT: OnceCell<Octocrab> = OnceCell::const_new();
+
+/// URL of an avatar scaled for display, with room for HiDPI outputs.
+pub fn sized_url(avatar_url: &str) -> String {
+    let separator = if avatar_url.contains('?') { '&' } else { '?' };
+    format!("{avatar_url}{separator}s={}", SIZE * 2)
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    Some(cache::dir()?.join("avatars"))
+}
+
+/// Path of the cached image for `url`; its ETag is stored next to it.
+fn cache_path(url: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    Some(cache_dir()?.join(format!("{:016x}", hasher.finish())))
+}
+
+fn etag_path(path: &Path) -> PathBuf {
+    path.with_extension("etag")
+}
+
+struct Cached {
+    bytes: Vec<u8>,
+    etag: Option<String>,
+    fresh: bool,
+}
+
+async fn read_cached(path: &Path) -> Option<Cached> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    let modified = tokio::fs::metadata(path).await.ok()?.modified().ok()?;
+    let etag = tokio::fs::read_to_string(etag_path(path)).await.ok();
+    Some(Cached {
+        bytes,
+        etag,
+        fresh: modified.elapsed().is_ok_and(|age| age < MAX_AGE),
+    })
+}
+
+/// Stores a downloaded avatar. The cache is best-effort, so failures are only logged.
+async fn store(path: &Path, bytes: &[u8], etag: Option<&str>) {
+    let result = async {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(path, bytes).await?;
+        match etag {
+            Some(etag) => tokio::fs::write(etag_path(path), etag).await,
+            None => match tokio::fs::remove_file(etag_path(path)).await {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+                _ => Ok(()),
+            },
+        }
+    }
+    .await;
+
+    if let Err(error) = result {
+        eprintln!("failed to cache avatar at {}: {error}", path.display());
+    }
+}
+
+/// Marks a revalidated avatar as fresh again.
+async fn touch(path: &Path) {
+    let result = async {
+        let file = tokio::fs::File::options().write(true).open(path).await?;
+        file.into_std().await.set_modified(SystemTime::now())
+    }
+    .await;
+
+    if let Err(error) = result {
+        eprintln!("failed to refresh cached avatar at {}: {error}", path.display());
+    }
+}
+
+enum Download {
+    NotModified,
+    Modified { bytes: Vec<u8>, etag: Option<String> },
+}
+
+async fn download(url: &str, etag: Option<&str>) -> Result<Download, Error> {
+    let client = CLIENT
+        .get_or_try_init(|| async { Octocrab::builder().build() })
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    if let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(IF_NONE_MATCH, etag);
+    }
+
+    let response = client._get_with_headers(url, Some(headers)).await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Download::NotModified);
+    }
+
+    let response = octocrab::map_github_error(response).await?;
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(ToOwned::to_owned);
+    let body = Limited::new(response.into_body(), MAX_IMAGE_BYTES)
+        .collect()
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    Ok(Download::Modified {
+        bytes: body.to_bytes().to_vec(),
+        etag,
+    })
+}
+
+/// Returns the image at `url`, from the cache when it is still current.
+pub async fn fetch(url: String) -> Result<Vec<u8>, Error> {
+    let path = cache_path(&url);
+    let cached = match &path {
+        Some(path) => read_cached(path).await,
+        None => None,
+    };
+
+    if let Some(cached) = cached.as_ref().filter(|cached| cached.fresh) {
+        return Ok(cached.bytes.clone());
+    }
+
+    let etag = cached.as_ref().and_then(|cached| cached.etag.as_deref());
+    match (download(&url, etag).await?, cached) {
+        (Download::NotModified, Some(cached)) => {
+            if let Some(path) = &path {
+                touch(path).await;
+            }
+            Ok(cached.bytes)
+        }
+        (Download::NotModified, None) => Err(Error::new(
+            ErrorKind::Other,
+            "GitHub answered 304 Not Modified without a cached copy",
+        )),
+        (Download::Modified { bytes, etag }, _) => {
+            if let Some(path) = &path {
+                store(path, &bytes, etag.as_deref()).await;
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+/// Evicts the least recently refreshed avatars until the cache fits in [`MAX_CACHE_BYTES`].
+pub async fn prune() {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+
+    let result = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        let mut images = Vec::new();
+        let mut total = 0;
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            total += metadata.len();
+            if entry.path().extension().is_none() {
+                images.push((metadata.modified()?, metadata.len(), entry.path()));
+            }
+        }
+
+        images.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in images {
+            if total <= MAX_CACHE_BYTES {
+                break;
+            }
+            std::fs::remove_file(&path)?;
+            let _ = std::fs::remove_file(etag_path(&path));
+            total = total.saturating_sub(len);
+        }
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(Err(error)) if error.kind() != std::io::ErrorKind::NotFound => {
+            eprintln!("failed to prune the avatar cache: {error}");
+        }
+        Err(error) => eprintln!("failed to prune the avatar cache: {error}"),
+        _ => {}
+    }
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! On-disk cache of subject details, so that reopening the popup or restarting the applet
+//! doesn't fetch threads that haven't changed.
+//!
+//! Enrichments are keyed by subject URL and remember the thread's `updated_at` they were
+//! fetched for. The REST responses they were built from are kept along with their ETags, so
+//! that refetching a changed thread only downloads what actually changed.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::enrichment::Enrichment;
+
+/// Entries older than this are dropped when the cache is saved.
+const MAX_AGE: chrono::TimeDelta = chrono::TimeDelta::days(30);
+
+/// Held for the whole of a save, so that overlapping saves share the partial file one at a
+/// time and finish in the order they were started.
+static SAVING: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// A REST response kept for ETag revalidation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: serde_json::Value,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Cached REST responses by URL, shared with the requests in flight.
+pub type Responses = Arc<Mutex<HashMap<String, CachedResponse>>>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    enrichments: HashMap<String, Enrichment>,
+    responses: HashMap<String, CachedResponse>,
+}
+
+/// The applet's cache directory, `$XDG_CACHE_HOME/git-work`.
+pub fn dir() -> Option<PathBuf> {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("git-work"))
+}
+
+fn path() -> Option<PathBuf> {
+    Some(dir()?.join("enrichments.json"))
+}
+
+/// Reads the cached enrichments and responses; a missing or unreadable cache is empty.
+pub fn load() -> (HashMap<String, Enrichment>, Responses) {
+    let file = path()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|bytes| match serde_json::from_slice::<CacheFile>(&bytes) {
+            Ok(file) => Some(file),
+            Err(error) => {
+                eprintln!("discarding unreadable enrichment cache: {error}");
+                None
+            }
+        })
+        .unwrap_or_default();
+
+    (file.enrichments, Arc::new(Mutex::new(file.responses)))
+}
+
+/// Writes the cache, dropping entries that are too old to be useful.
+pub async fn save(mut enrichments: HashMap<String, Enrichment>, responses: Responses) {
+    let Some(path) = path() else {
+        return;
+    };
+    let _saving = SAVING.lock().await;
+
+    let now = Utc::now();
+    enrichments.retain(|_, enrichment| now - enrichment.updated_at < MAX_AGE);
+    let mut responses = responses.lock().unwrap().clone();
+    responses.retain(|_, response| now - response.fetched_at < MAX_AGE);
+
+    let result = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        let bytes = serde_json::to_vec(&CacheFile {
+            enrichments,
+            responses,
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Write then rename, so a crash never leaves a truncated cache behind.
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(partial, path)
+    })
+    .await;
+
+    match result {
+        Ok(Err(error)) => eprintln!("failed to save the enrichment cache: {error}"),
+        Err(error) => eprintln!("failed to save the enrichment cache: {error}"),
+        Ok(Ok(())) => {}
+    }
+}
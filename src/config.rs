@@ -1,9 +1,85 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
 pub struct Config {
     demo: String,
+    /// Action triggered by middle-clicking the panel button.
+    pub middle_click_action: PanelAction,
+    /// Command used to open links, with `{url}`, `{repo}` and `{number}` placeholders.
+    /// Empty means the desktop's default handler.
+    pub opener_command: String,
+    /// Labels shown on items; `*` at the end matches any suffix. Empty shows every label.
+    pub label_allowlist: Vec<String>,
+}
+
+impl Config {
+    /// Whether a label passes the allowlist, ignoring case.
+    pub fn shows_label(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.label_allowlist.is_empty()
+            || self.label_allowlist.iter().any(|pattern| {
+                let pattern = pattern.to_lowercase();
+                match pattern.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => name == pattern,
+                }
+            })
+    }
+}
+
+/// Actions offered from the panel button's context menu.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PanelAction {
+    #[default]
+    Refresh,
+    MarkAllRead,
+    PausePolling,
+    OpenInbox,
+    Settings,
+}
+
+impl PanelAction {
+    pub const ALL: [PanelAction; 5] = [
+        PanelAction::Refresh,
+        PanelAction::MarkAllRead,
+        PanelAction::PausePolling,
+        PanelAction::OpenInbox,
+        PanelAction::Settings,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_allowlist() {
+        let cases = [
+            (vec![], "anything", true),
+            (vec!["bug"], "bug", true),
+            (vec!["bug"], "Bug", true),
+            (vec!["bug"], "bugfix", false),
+            (vec!["priority/*"], "priority/high", true),
+            (vec!["priority/*"], "Priority/Low", true),
+            (vec!["priority/*"], "priority/", true),
+            (vec!["priority/*"], "priority", false),
+            (vec!["Area:*"], "area: wayland", true),
+            (vec!["*"], "anything", true),
+            (vec!["bug", "good first *"], "good first issue", true),
+            (vec!["bug", "good first *"], "enhancement", false),
+            (vec!["wontfix"], "wontfix*", false),
+        ];
+
+        for (allowlist, name, expected) in cases {
+            let config = Config {
+                label_allowlist: allowlist.iter().map(ToString::to_string).collect(),
+                ..Config::default()
+            };
+            assert_eq!(config.shows_label(name), expected, "{allowlist:?} with {name}");
+        }
+    }
 }
@@ -0,0 +1,1139 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Details about a notification's subject that the notifications API doesn't include.
+//!
+//! Issues and pull requests are resolved in batches through a single GraphQL query; other
+//! subjects, and everything when GraphQL is unavailable, go through the REST API one by one.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use http::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+use http::StatusCode;
+use http_body_util::BodyExt;
+use octocrab::models::activity::Notification;
+use octocrab::Octocrab;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+use crate::cache::{CachedResponse, Responses};
+use crate::github::{Error, ErrorKind};
+
+/// State of a pull request, as shown in GitHub's web inbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PullState {
+    Open,
+    Draft,
+    Merged,
+    Closed,
+}
+
+/// Combined result of the check runs and commit statuses of a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckState {
+    Passing,
+    Failing,
+    Pending,
+}
+
+/// CI status of a pull request's head commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checks {
+    pub state: CheckState,
+    /// Web page of the first failing check run or status.
+    pub failing_url: Option<String>,
+}
+
+/// Overall review state of a pull request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+/// Review progress of a pull request, from the signed-in user's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub decision: ReviewDecision,
+    /// Whether the user has submitted a review.
+    pub reviewed_by_viewer: bool,
+    /// Whether the user is still among the requested reviewers.
+    pub viewer_requested: bool,
+    /// Whether a review is still requested from a team, which may include the user.
+    #[serde(default)]
+    pub team_requested: bool,
+    /// Whether commits were pushed after the user's last review.
+    pub new_commits_since_review: bool,
+}
+
+/// A label of an issue or pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    pub name: String,
+    /// Hex RGB color without the leading `#`, as GitHub returns it.
+    pub color: String,
+}
+
+/// The comment that last updated a thread, or the subject itself when nobody commented yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub author: String,
+    pub avatar_url: Option<String>,
+    /// The first few lines of the body.
+    pub snippet: String,
+    pub html_url: Option<String>,
+}
+
+/// Extra details about a notification's subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enrichment {
+    /// The thread's `updated_at` this was fetched for.
+    pub updated_at: DateTime<Utc>,
+    /// When this was fetched; entries cached before it was recorded count as old.
+    #[serde(default)]
+    pub fetched_at: DateTime<Utc>,
+    pub pull_state: Option<PullState>,
+    pub checks: Option<Checks>,
+    pub labels: Vec<Label>,
+    /// Only fetched for review requests.
+    pub review: Option<Review>,
+    pub comment: Option<Comment>,
+    /// Avatar of whoever opened the pull request.
+    pub author_avatar_url: Option<String>,
+}
+
+/// GraphQL rate limit, as reported by the last batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// Points the last query cost.
+    pub cost: i64,
+    /// Points left until `reset_at`.
+    pub remaining: i64,
+    pub reset_at: DateTime<Utc>,
+}
+
+/// Result of a batched lookup.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// Enrichments by cache key.
+    pub enrichments: Vec<(String, Result<Enrichment, String>)>,
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// How long pending checks are trusted before they are fetched again.
+const PENDING_CHECKS_MAX_AGE: TimeDelta = TimeDelta::minutes(1);
+/// How long finished checks are trusted; re-runs don't touch the thread's `updated_at`.
+const CHECKS_MAX_AGE: TimeDelta = TimeDelta::minutes(10);
+/// How long anything else is trusted; labels and avatars change without touching the thread
+/// either. REST subjects revalidate through their ETags, so this is usually a `304`.
+const MAX_AGE: TimeDelta = TimeDelta::hours(1);
+
+impl Enrichment {
+    /// Whether this still describes the notification, i.e. the thread has no newer activity.
+    pub fn is_fresh_for(&self, notification: &Notification) -> bool {
+        self.updated_at == notification.updated_at
+    }
+
+    /// Whether this should be fetched again. Besides new thread activity, CI status expires
+    /// on its own, since checks finishing don't update the thread, and everything else is
+    /// revalidated after [`MAX_AGE`].
+    pub fn needs_refresh(&self, notification: &Notification, now: DateTime<Utc>) -> bool {
+        let age = now - self.fetched_at;
+        !self.is_fresh_for(notification)
+            || age >= MAX_AGE
+            || match &self.checks {
+                Some(checks) if checks.state == CheckState::Pending => {
+                    age >= PENDING_CHECKS_MAX_AGE
+                }
+                Some(_) => age >= CHECKS_MAX_AGE,
+                None => false,
+            }
+    }
+}
+
+/// Key under which a notification's enrichment is cached: its subject's API URL.
+pub fn cache_key(notification: &Notification) -> Option<String> {
+    notification.subject.url.as_ref().map(ToString::to_string)
+}
+
+/// Owner, repository and number of an issue or pull request subject.
+fn subject_target(notification: &Notification) -> Option<(String, String, u64)> {
+    let url = notification.subject.url.as_ref()?;
+    let segments: Vec<_> = url.path_segments()?.collect();
+    match segments.as_slice() {
+        ["repos", owner, repo, "issues" | "pulls", number] => {
+            Some((owner.to_string(), repo.to_string(), number.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Whether this notification can be resolved by [`fetch_batch`].
+pub fn is_batchable(notification: &Notification) -> bool {
+    matches!(notification.subject.r#type.as_str(), "PullRequest" | "Issue")
+        && subject_target(notification).is_some()
+}
+
+/// Whether there is anything to fetch for this notification.
+pub fn is_enrichable(notification: &Notification) -> bool {
+    notification.subject.url.is_some()
+        && (matches!(notification.subject.r#type.as_str(), "PullRequest" | "Issue")
+            || notification.subject.latest_comment_url.is_some())
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    merged: bool,
+    head: CommitRef,
+    user: Option<User>,
+    #[serde(default)]
+    requested_reviewers: Vec<User>,
+    #[serde(default)]
+    requested_teams: Vec<Team>,
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Team {}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+    avatar_url: Option<String>,
+}
+
+/// Anything `latest_comment_url` can point to: a comment, an issue, a pull request or a release.
+#[derive(Debug, Deserialize)]
+struct Post {
+    user: Option<User>,
+    /// Releases name their creator `author`.
+    author: Option<User>,
+    body: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullReview {
+    user: Option<User>,
+    state: String,
+    commit_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    state: String,
+    target_url: Option<String>,
+}
+
+fn is_failing_conclusion(conclusion: &str) -> bool {
+    matches!(
+        conclusion.to_ascii_lowercase().as_str(),
+        "failure" | "timed_out" | "cancelled" | "action_required" | "startup_failure"
+    )
+}
+
+/// Overall decision from each reviewer's latest approving or blocking review.
+fn review_decision<'a>(states: impl IntoIterator<Item = &'a str>) -> ReviewDecision {
+    let states: Vec<_> = states.into_iter().collect();
+    if states.contains(&"CHANGES_REQUESTED") {
+        ReviewDecision::ChangesRequested
+    } else if states.contains(&"APPROVED") {
+        ReviewDecision::Approved
+    } else {
+        ReviewDecision::ReviewRequired
+    }
+}
+
+impl PullRequest {
+    fn pull_state(&self) -> PullState {
+        if self.merged {
+            PullState::Merged
+        } else if self.state == "closed" {
+            PullState::Closed
+        } else if self.draft {
+            PullState::Draft
+        } else {
+            PullState::Open
+        }
+    }
+}
+
+/// Fetches `url`, revalidating the cached response with its ETag.
+///
+/// GitHub doesn't count `304 Not Modified` answers against the rate limit.
+async fn get<T: DeserializeOwned>(
+    client: &Octocrab,
+    responses: &Responses,
+    url: &str,
+) -> Result<T, Error> {
+    let cached = responses.lock().unwrap().get(url).cloned();
+
+    let mut headers = HeaderMap::new();
+    if let Some(etag) = cached
+        .as_ref()
+        .and_then(|cached| HeaderValue::from_str(&cached.etag).ok())
+    {
+        headers.insert(IF_NONE_MATCH, etag);
+    }
+
+    let response = client._get_with_headers(url, Some(headers)).await?;
+    let body = match cached {
+        Some(cached) if response.status() == StatusCode::NOT_MODIFIED => cached.body,
+        _ => {
+            let response = octocrab::map_github_error(response).await?;
+            let etag = response
+                .headers()
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(ToOwned::to_owned);
+            let bytes = response.into_body().collect().await?.to_bytes();
+            let body: serde_json::Value = serde_json::from_slice(&bytes)
+                .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+            let mut responses = responses.lock().unwrap();
+            match etag {
+                Some(etag) => {
+                    responses.insert(
+                        url.to_owned(),
+                        CachedResponse {
+                            etag,
+                            body: body.clone(),
+                            fetched_at: Utc::now(),
+                        },
+                    );
+                }
+                None => {
+                    responses.remove(url);
+                }
+            }
+            body
+        }
+    };
+
+    serde_json::from_value(body).map_err(|e| Error::new(ErrorKind::Other, e.to_string()))
+}
+
+/// Fetches the check runs and commit statuses of `sha` in the repository at `repo_url`.
+async fn fetch_checks(
+    client: &Octocrab,
+    responses: &Responses,
+    repo_url: &str,
+    sha: &str,
+) -> Result<Option<Checks>, Error> {
+    let runs: CheckRuns = get(
+        client,
+        responses,
+        &format!("{repo_url}/commits/{sha}/check-runs?per_page=100"),
+    )
+    .await?;
+    let status: CombinedStatus =
+        get(client, responses, &format!("{repo_url}/commits/{sha}/status")).await?;
+
+    // (state, url) of every check run and status.
+    let results = runs
+        .check_runs
+        .into_iter()
+        .map(|run| {
+            let state = match (run.status.as_str(), run.conclusion.as_deref()) {
+                ("completed", Some(conclusion)) if is_failing_conclusion(conclusion) => {
+                    CheckState::Failing
+                }
+                ("completed", _) => CheckState::Passing,
+                _ => CheckState::Pending,
+            };
+            (state, run.html_url)
+        })
+        .chain(status.statuses.into_iter().map(|status| {
+            let state = match status.state.as_str() {
+                "success" => CheckState::Passing,
+                "failure" | "error" => CheckState::Failing,
+                _ => CheckState::Pending,
+            };
+            (state, status.target_url)
+        }))
+        .collect::<Vec<_>>();
+
+    if results.is_empty() {
+        return Ok(None);
+    }
+
+    let failing_url = results
+        .iter()
+        .find(|(state, _)| *state == CheckState::Failing)
+        .map(|(_, url)| url.clone());
+    let state = if failing_url.is_some() {
+        CheckState::Failing
+    } else if results.iter().any(|(state, _)| *state == CheckState::Pending) {
+        CheckState::Pending
+    } else {
+        CheckState::Passing
+    };
+
+    Ok(Some(Checks {
+        state,
+        failing_url: failing_url.flatten(),
+    }))
+}
+
+/// Maximum number of lines shown in a comment snippet.
+const SNIPPET_LINES: usize = 3;
+/// Maximum number of characters shown in a comment snippet.
+const SNIPPET_CHARS: usize = 200;
+
+/// The first lines of a Markdown body, leaving out blank lines and quoted replies.
+fn snippet(body: &str) -> String {
+    let text = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('>'))
+        .take(SNIPPET_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match text.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Fetches the comment at `url`.
+async fn fetch_comment(
+    client: &Octocrab,
+    responses: &Responses,
+    url: &str,
+) -> Result<Option<Comment>, Error> {
+    let post: Post = get(client, responses, url).await?;
+    let Some(author) = post.user.or(post.author) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Comment {
+        author: author.login,
+        avatar_url: author.avatar_url,
+        snippet: post.body.as_deref().map(snippet).unwrap_or_default(),
+        html_url: post.html_url,
+    }))
+}
+
+/// Fetches the reviews of the pull request at `pull_url` and summarizes them for `viewer`.
+async fn fetch_review(
+    client: &Octocrab,
+    responses: &Responses,
+    pull_url: &str,
+    pull: &PullRequest,
+    viewer: &str,
+) -> Result<Review, Error> {
+    let reviews: Vec<PullReview> =
+        get(client, responses, &format!("{pull_url}/reviews?per_page=100")).await?;
+
+    // Latest decisive review per reviewer; comments don't change a reviewer's verdict.
+    let mut verdicts: Vec<(&str, &str)> = Vec::new();
+    for review in &reviews {
+        let Some(user) = &review.user else {
+            continue;
+        };
+        if !matches!(
+            review.state.as_str(),
+            "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+        ) {
+            continue;
+        }
+        match verdicts.iter_mut().find(|(login, _)| *login == user.login) {
+            Some(verdict) => verdict.1 = &review.state,
+            None => verdicts.push((&user.login, &review.state)),
+        }
+    }
+
+    let decision = review_decision(verdicts.iter().map(|(_, state)| *state));
+
+    let last_viewer_review = reviews.iter().rev().find(|review| {
+        review.state != "PENDING" && review.user.as_ref().is_some_and(|user| user.login == viewer)
+    });
+
+    Ok(Review {
+        decision,
+        reviewed_by_viewer: last_viewer_review.is_some(),
+        viewer_requested: pull
+            .requested_reviewers
+            .iter()
+            .any(|user| user.login == viewer),
+        team_requested: !pull.requested_teams.is_empty(),
+        new_commits_since_review: last_viewer_review
+            .and_then(|review| review.commit_id.as_deref())
+            .is_some_and(|commit| commit != pull.head.sha),
+    })
+}
+
+/// Fetches the details of a notification's subject.
+///
+/// Only failing to fetch the subject itself fails the whole enrichment; checks, reviews and
+/// the latest comment are left out when they can't be fetched.
+///
+/// `viewer` caches the signed-in user's login across calls, and `responses` the REST
+/// responses for revalidation.
+pub async fn fetch(
+    client: Octocrab,
+    viewer: Arc<OnceCell<String>>,
+    responses: Responses,
+    notification: Notification,
+) -> Result<Enrichment, Error> {
+    let mut enrichment = Enrichment {
+        updated_at: notification.updated_at,
+        fetched_at: Utc::now(),
+        pull_state: None,
+        checks: None,
+        labels: Vec::new(),
+        review: None,
+        comment: None,
+        author_avatar_url: None,
+    };
+
+    if let Some(url) = &notification.subject.url {
+        if notification.subject.r#type == "Issue" {
+            let issue: Issue = get(&client, &responses, url.as_str()).await?;
+            enrichment.labels = issue.labels;
+        } else if notification.subject.r#type == "PullRequest" {
+            let pull: PullRequest = get(&client, &responses, url.as_str()).await?;
+            enrichment.pull_state = Some(pull.pull_state());
+            enrichment.author_avatar_url =
+                pull.user.as_ref().and_then(|user| user.avatar_url.clone());
+            enrichment.checks = fetch_checks(
+                &client,
+                &responses,
+                notification.repository.url.as_str(),
+                &pull.head.sha,
+            )
+            .await
+            .unwrap_or_else(|error| {
+                // E.g. fine-grained tokens without the Checks permission get a 403.
+                eprintln!("failed to fetch checks of {url}: {error}");
+                None
+            });
+
+            if notification.reason == "review_requested" {
+                let review = async {
+                    let viewer = viewer
+                        .get_or_try_init(|| async {
+                            client.current().user().await.map(|user| user.login)
+                        })
+                        .await?;
+                    fetch_review(&client, &responses, url.as_str(), &pull, viewer).await
+                };
+                enrichment.review = review
+                    .await
+                    .map_err(|error| eprintln!("failed to fetch reviews of {url}: {error}"))
+                    .ok();
+            }
+            enrichment.labels = pull.labels;
+        }
+    }
+
+    if let Some(url) = &notification.subject.latest_comment_url {
+        // The comment may have been deleted since.
+        enrichment.comment = fetch_comment(&client, &responses, url.as_str())
+            .await
+            .unwrap_or_else(|error| {
+                eprintln!("failed to fetch comment {url}: {error}");
+                None
+            });
+    }
+
+    Ok(enrichment)
+}
+
+/// Fields fetched for every issue or pull request in a batch.
+const SUBJECT_FRAGMENTS: &str = r#"
+fragment Actor on Actor { login avatarUrl }
+fragment LastComment on IssueComment { author { ...Actor } body url databaseId }
+fragment IssueFields on Issue {
+  author { ...Actor } body url
+  labels(first: 20) { nodes { name color } }
+  comments(last: 1) { nodes { ...LastComment } }
+}
+fragment PullFields on PullRequest {
+  author { ...Actor } body url state isDraft headRefOid
+  labels(first: 20) { nodes { name color } }
+  comments(last: 1) { nodes { ...LastComment } }
+  viewerLatestReview { commit { oid } }
+  latestOpinionatedReviews(first: 20) { nodes { state } }
+  reviewRequests(first: 20) {
+    nodes { requestedReviewer { __typename ... on User { login } } }
+  }
+  commits(last: 1) {
+    nodes {
+      commit {
+        statusCheckRollup {
+          state
+          contexts(first: 50) {
+            nodes {
+              __typename
+              ... on CheckRun { conclusion detailsUrl }
+              ... on StatusContext { state targetUrl }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct GraphResponse {
+    data: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    errors: Vec<GraphError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Connection<T> {
+    #[serde(default)]
+    nodes: Vec<Option<T>>,
+}
+
+impl<T> Connection<T> {
+    fn into_nodes(self) -> impl Iterator<Item = T> {
+        self.nodes.into_iter().flatten()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphRepository {
+    issue_or_pull_request: Option<GraphSubject>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphActor {
+    login: Option<String>,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphComment {
+    author: Option<GraphActor>,
+    body: Option<String>,
+    url: Option<String>,
+    database_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphSubject {
+    author: Option<GraphActor>,
+    body: Option<String>,
+    url: Option<String>,
+    state: Option<String>,
+    #[serde(default)]
+    is_draft: bool,
+    head_ref_oid: Option<String>,
+    labels: Option<Connection<Label>>,
+    comments: Option<Connection<GraphComment>>,
+    viewer_latest_review: Option<GraphReview>,
+    latest_opinionated_reviews: Option<Connection<GraphReviewState>>,
+    review_requests: Option<Connection<GraphReviewRequest>>,
+    commits: Option<Connection<GraphCommitNode>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphReview {
+    commit: Option<GraphCommitOid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphCommitOid {
+    oid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphReviewState {
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphReviewRequest {
+    requested_reviewer: Option<GraphReviewer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphReviewer {
+    #[serde(rename = "__typename")]
+    typename: String,
+    login: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphCommitNode {
+    commit: GraphCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphCommit {
+    status_check_rollup: Option<GraphRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphRollup {
+    state: String,
+    contexts: Connection<GraphContext>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphContext {
+    #[serde(rename = "__typename")]
+    typename: String,
+    conclusion: Option<String>,
+    details_url: Option<String>,
+    state: Option<String>,
+    target_url: Option<String>,
+}
+
+impl GraphActor {
+    fn into_comment(self, body: Option<String>, url: Option<String>) -> Option<Comment> {
+        Some(Comment {
+            author: self.login?,
+            avatar_url: self.avatar_url,
+            snippet: body.as_deref().map(snippet).unwrap_or_default(),
+            html_url: url,
+        })
+    }
+}
+
+impl GraphRollup {
+    fn into_checks(self) -> Checks {
+        let state = match self.state.as_str() {
+            "SUCCESS" => CheckState::Passing,
+            "FAILURE" | "ERROR" => CheckState::Failing,
+            _ => CheckState::Pending,
+        };
+        let failing_url = self.contexts.into_nodes().find_map(|context| {
+            let failing = match context.typename.as_str() {
+                "CheckRun" => context.conclusion.as_deref().is_some_and(is_failing_conclusion),
+                _ => matches!(context.state.as_deref(), Some("FAILURE" | "ERROR")),
+            };
+            failing.then(|| context.details_url.or(context.target_url))
+        });
+
+        Checks {
+            state,
+            failing_url: failing_url.flatten(),
+        }
+    }
+}
+
+impl GraphSubject {
+    fn into_enrichment(self, notification: &Notification, viewer: Option<&str>) -> Enrichment {
+        let is_pull = notification.subject.r#type == "PullRequest";
+
+        let pull_state = is_pull.then(|| match self.state.as_deref() {
+            Some("MERGED") => PullState::Merged,
+            Some("CLOSED") => PullState::Closed,
+            _ if self.is_draft => PullState::Draft,
+            _ => PullState::Open,
+        });
+
+        let checks = self
+            .commits
+            .and_then(|commits| commits.into_nodes().last())
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(GraphRollup::into_checks);
+
+        let review = (is_pull && notification.reason == "review_requested").then(|| {
+            let last_review = self.viewer_latest_review.as_ref();
+            let reviewers: Vec<_> = self
+                .review_requests
+                .iter()
+                .flat_map(|requests| requests.nodes.iter().flatten())
+                .filter_map(|request| request.requested_reviewer.as_ref())
+                .collect();
+            Review {
+                decision: review_decision(
+                    self.latest_opinionated_reviews
+                        .iter()
+                        .flat_map(|reviews| reviews.nodes.iter().flatten())
+                        .map(|review| review.state.as_str()),
+                ),
+                reviewed_by_viewer: last_review.is_some(),
+                viewer_requested: reviewers
+                    .iter()
+                    .any(|reviewer| viewer.is_some() && reviewer.login.as_deref() == viewer),
+                team_requested: reviewers.iter().any(|reviewer| reviewer.typename == "Team"),
+                new_commits_since_review: last_review
+                    .and_then(|review| review.commit.as_ref())
+                    .is_some_and(|commit| Some(&commit.oid) != self.head_ref_oid.as_ref()),
+            }
+        });
+
+        let author_avatar_url = self
+            .author
+            .as_ref()
+            .and_then(|author| author.avatar_url.clone())
+            .filter(|_| is_pull);
+
+        // The latest comment is the subject itself until somebody replies.
+        let comment = match &notification.subject.latest_comment_url {
+            Some(latest) if Some(latest) == notification.subject.url.as_ref() => self
+                .author
+                .and_then(|author| author.into_comment(self.body, self.url)),
+            // Only preview the last issue comment if it's the one that triggered the
+            // notification; review comments and reviews aren't part of `comments`.
+            Some(latest) => {
+                let id = latest
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .and_then(|id| id.parse().ok());
+                self.comments
+                    .and_then(|comments| comments.into_nodes().last())
+                    .filter(|comment| id.is_some() && comment.database_id == id)
+                    .and_then(|comment| comment.author?.into_comment(comment.body, comment.url))
+            }
+            None => None,
+        };
+
+        Enrichment {
+            updated_at: notification.updated_at,
+            fetched_at: Utc::now(),
+            pull_state,
+            checks,
+            labels: self
+                .labels
+                .map(|labels| labels.into_nodes().collect())
+                .unwrap_or_default(),
+            review,
+            comment,
+            author_avatar_url,
+        }
+    }
+}
+
+/// Fetches the details of several issue and pull request subjects in one GraphQL query.
+///
+/// Notifications that aren't [batchable](is_batchable) are reported as failed.
+pub async fn fetch_batch(
+    client: Octocrab,
+    viewer: Arc<OnceCell<String>>,
+    notifications: Vec<Notification>,
+) -> Result<Batch, Error> {
+    let mut params = Vec::new();
+    let mut fields = String::new();
+    let mut variables = serde_json::Map::new();
+    for (i, notification) in notifications.iter().enumerate() {
+        let Some((owner, repo, number)) = subject_target(notification) else {
+            continue;
+        };
+        params.push(format!("$o{i}: String!, $r{i}: String!, $n{i}: Int!"));
+        fields.push_str(&format!(
+            "n{i}: repository(owner: $o{i}, name: $r{i}) {{ \
+             issueOrPullRequest(number: $n{i}) {{ ...IssueFields ...PullFields }} }}\n"
+        ));
+        variables.insert(format!("o{i}"), owner.into());
+        variables.insert(format!("r{i}"), repo.into());
+        variables.insert(format!("n{i}"), number.into());
+    }
+    if params.is_empty() {
+        return Ok(Batch {
+            enrichments: Vec::new(),
+            rate_limit: None,
+        });
+    }
+
+    let query = format!(
+        "query({}) {{\nrateLimit {{ cost remaining resetAt }}\nviewer {{ login }}\n{fields}}}\n\
+         {SUBJECT_FRAGMENTS}",
+        params.join(", ")
+    );
+    let response: GraphResponse = client
+        .graphql(&serde_json::json!({ "query": query, "variables": variables }))
+        .await?;
+
+    let Some(mut data) = response.data else {
+        let messages: Vec<_> = response.errors.into_iter().map(|e| e.message).collect();
+        return Err(Error::new(ErrorKind::Other, messages.join("; ")));
+    };
+
+    let rate_limit = data
+        .remove("rateLimit")
+        .and_then(|value| serde_json::from_value(value).ok());
+    if let Some(login) = data
+        .remove("viewer")
+        .and_then(|value| value.get("login")?.as_str().map(ToOwned::to_owned))
+    {
+        let _ = viewer.set(login);
+    }
+
+    let enrichments = notifications
+        .iter()
+        .enumerate()
+        .filter_map(|(i, notification)| {
+            let key = cache_key(notification)?;
+            let subject = data
+                .remove(&format!("n{i}"))
+                .and_then(|value| serde_json::from_value::<GraphRepository>(value).ok())
+                .and_then(|repository| repository.issue_or_pull_request);
+            let result = match subject {
+                Some(subject) => {
+                    Ok(subject.into_enrichment(notification, viewer.get().map(String::as_str)))
+                }
+                None => Err(String::from("not found or not accessible")),
+            };
+            Some((key, result))
+        })
+        .collect();
+
+    Ok(Batch {
+        enrichments,
+        rate_limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTIFICATIONS: &str = include_str!("testdata/notifications.json");
+    /// GraphQL batch response in the shape `SUBJECT_FRAGMENTS` asks for.
+    const SUBJECTS: &str = include_str!("testdata/graphql_subjects.json");
+
+    fn notification(id: &str) -> Notification {
+        let notifications: Vec<Notification> = serde_json::from_str(NOTIFICATIONS).unwrap();
+        notifications
+            .into_iter()
+            .find(|n| n.id.to_string() == id)
+            .unwrap_or_else(|| panic!("no notification {id} in the fixture"))
+    }
+
+    fn subject(alias: &str) -> GraphSubject {
+        let mut response: GraphResponse = serde_json::from_str(SUBJECTS).unwrap();
+        let repository = response.data.as_mut().unwrap().remove(alias).unwrap();
+        serde_json::from_value::<GraphRepository>(repository)
+            .unwrap()
+            .issue_or_pull_request
+            .unwrap_or_else(|| panic!("no subject {alias} in the fixture"))
+    }
+
+    #[test]
+    fn enrichments() {
+        let jackpot = "https://avatars.githubusercontent.com/u/1419717?v=4";
+        let mmstick = "https://avatars.githubusercontent.com/u/4342167?v=4";
+        let pull = "https://github.com/pop-os/cosmic-epoch/pull/1412";
+        let cases = [
+            (
+                "n0",
+                "15321608801",
+                Enrichment {
+                    updated_at: DateTime::default(),
+                    fetched_at: DateTime::default(),
+                    pull_state: Some(PullState::Open),
+                    checks: Some(Checks {
+                        state: CheckState::Failing,
+                        failing_url: Some(
+                            "https://github.com/pop-os/cosmic-epoch/actions/runs/11880001/job/33102"
+                                .to_string(),
+                        ),
+                    }),
+                    labels: vec![Label {
+                        name: "enhancement".to_string(),
+                        color: "a2eeef".to_string(),
+                    }],
+                    review: Some(Review {
+                        decision: ReviewDecision::Approved,
+                        reviewed_by_viewer: true,
+                        viewer_requested: true,
+                        team_requested: true,
+                        new_commits_since_review: true,
+                    }),
+                    comment: Some(Comment {
+                        author: "mmstick".to_string(),
+                        avatar_url: Some(mmstick.to_string()),
+                        snippet: "Looks good overall.\nOne nit below.".to_string(),
+                        html_url: Some(format!("{pull}#issuecomment-2871953361")),
+                    }),
+                    author_avatar_url: Some(jackpot.to_string()),
+                },
+            ),
+            // The last issue comment isn't the one the notification points at.
+            (
+                "n1",
+                "15321608801",
+                Enrichment {
+                    updated_at: DateTime::default(),
+                    fetched_at: DateTime::default(),
+                    pull_state: Some(PullState::Draft),
+                    checks: None,
+                    labels: Vec::new(),
+                    review: Some(Review {
+                        decision: ReviewDecision::ReviewRequired,
+                        reviewed_by_viewer: false,
+                        viewer_requested: false,
+                        team_requested: false,
+                        new_commits_since_review: false,
+                    }),
+                    comment: None,
+                    author_avatar_url: Some(jackpot.to_string()),
+                },
+            ),
+            // Nobody commented yet, so the issue itself is the latest comment.
+            (
+                "n2",
+                "15321608802",
+                Enrichment {
+                    updated_at: DateTime::default(),
+                    fetched_at: DateTime::default(),
+                    pull_state: None,
+                    checks: None,
+                    labels: vec![Label {
+                        name: "bug".to_string(),
+                        color: "d73a4a".to_string(),
+                    }],
+                    review: None,
+                    comment: Some(Comment {
+                        author: "mmstick".to_string(),
+                        avatar_url: Some(mmstick.to_string()),
+                        snippet: "The applet panics once the token expires:\n```\n\
+                                  thread 'main' panicked"
+                            .to_string(),
+                        html_url: Some(
+                            "https://github.com/pop-os/cosmic-epoch/issues/1398".to_string(),
+                        ),
+                    }),
+                    author_avatar_url: None,
+                },
+            ),
+        ];
+
+        for (alias, id, expected) in cases {
+            let notification = notification(id);
+            let mut enrichment = subject(alias).into_enrichment(&notification, Some("edfloreshz"));
+            assert_eq!(enrichment.updated_at, notification.updated_at, "subject {alias}");
+            enrichment.updated_at = DateTime::default();
+            enrichment.fetched_at = DateTime::default();
+            assert_eq!(enrichment, expected, "subject {alias}");
+        }
+    }
+
+    #[test]
+    fn review_decisions() {
+        let cases: [(&[&str], ReviewDecision); 6] = [
+            (&[], ReviewDecision::ReviewRequired),
+            (&["APPROVED"], ReviewDecision::Approved),
+            (&["APPROVED", "CHANGES_REQUESTED"], ReviewDecision::ChangesRequested),
+            (&["CHANGES_REQUESTED", "APPROVED"], ReviewDecision::ChangesRequested),
+            (&["DISMISSED"], ReviewDecision::ReviewRequired),
+            (&["DISMISSED", "APPROVED"], ReviewDecision::Approved),
+        ];
+
+        for (states, expected) in cases {
+            assert_eq!(review_decision(states.iter().copied()), expected, "{states:?}");
+        }
+    }
+
+    #[test]
+    fn checks() {
+        let run = "https://github.com/pop-os/cosmic-epoch/actions/runs/11880001/job/33102";
+        let status = "https://ci.example.com/builds/512";
+        let cases = [
+            (
+                serde_json::json!({ "state": "SUCCESS", "contexts": { "nodes": [] } }),
+                CheckState::Passing,
+                None,
+            ),
+            (
+                serde_json::json!({ "state": "PENDING", "contexts": { "nodes": [
+                    { "__typename": "CheckRun", "conclusion": null, "detailsUrl": run }
+                ] } }),
+                CheckState::Pending,
+                None,
+            ),
+            (
+                serde_json::json!({ "state": "EXPECTED", "contexts": { "nodes": [] } }),
+                CheckState::Pending,
+                None,
+            ),
+            (
+                serde_json::json!({ "state": "FAILURE", "contexts": { "nodes": [
+                    { "__typename": "CheckRun", "conclusion": "SUCCESS", "detailsUrl": status },
+                    { "__typename": "CheckRun", "conclusion": "TIMED_OUT", "detailsUrl": run }
+                ] } }),
+                CheckState::Failing,
+                Some(run),
+            ),
+            (
+                serde_json::json!({ "state": "ERROR", "contexts": { "nodes": [
+                    { "__typename": "StatusContext", "state": "ERROR", "targetUrl": status }
+                ] } }),
+                CheckState::Failing,
+                Some(status),
+            ),
+            // A run that already failed is linked while the others are still going.
+            (
+                serde_json::json!({ "state": "PENDING", "contexts": { "nodes": [
+                    { "__typename": "CheckRun", "conclusion": "CANCELLED", "detailsUrl": run },
+                    null
+                ] } }),
+                CheckState::Pending,
+                Some(run),
+            ),
+        ];
+
+        for (rollup, state, failing_url) in cases {
+            let checks = serde_json::from_value::<GraphRollup>(rollup.clone())
+                .unwrap()
+                .into_checks();
+            assert_eq!(
+                checks,
+                Checks {
+                    state,
+                    failing_url: failing_url.map(ToString::to_string),
+                },
+                "{rollup}"
+            );
+        }
+    }
+}
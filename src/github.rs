@@ -1,30 +1,473 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use octocrab::models::activity::Notification;
+use octocrab::models::NotificationId;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
+/// Broad category of a failed GitHub request, used to pick the panel icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The token is missing, expired or lacks the required scopes.
+    Auth,
+    /// GitHub could not be reached at all.
+    Offline,
+    /// Any other API failure.
+    Other,
+}
+
+/// A failed GitHub request, reduced to something that can travel through messages.
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<octocrab::Error> for Error {
+    fn from(error: octocrab::Error) -> Self {
+        let kind = match &error {
+            octocrab::Error::GitHub { source, .. }
+                if matches!(source.status_code.as_u16(), 401 | 403) =>
+            {
+                ErrorKind::Auth
+            }
+            octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. } => ErrorKind::Offline,
+            _ => ErrorKind::Other,
+        };
+        Self::new(kind, error.to_string())
+    }
+}
+
+/// Marks a thread as done, removing it from the inbox (`DELETE /notifications/threads/{id}`).
+pub async fn mark_thread_done(client: &Octocrab, id: NotificationId) -> Result<(), Error> {
+    let response = client
+        ._delete(format!("/notifications/threads/{id}"), None::<&()>)
+        .await?;
+    octocrab::map_github_error(response).await?;
+    Ok(())
+}
+
+/// Marks every notification of a repository given as `owner/repo` as read.
+///
+/// Threads updated after `last_read_at` are left untouched.
+pub async fn mark_repo_as_read(
+    client: &Octocrab,
+    full_name: &str,
+    last_read_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), Error> {
+    let body = match last_read_at {
+        Some(last_read_at) => serde_json::json!({ "last_read_at": last_read_at }),
+        None => serde_json::json!({}),
+    };
+    let response = client
+        ._put(format!("/repos/{full_name}/notifications"), Some(&body))
+        .await?;
+    octocrab::map_github_error(response).await?;
+    Ok(())
+}
+
+/// How the user is subscribed to a notification thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionState {
+    /// Explicitly watching the thread.
+    Subscribed,
+    /// Notifications for the thread are ignored.
+    Ignored,
+    /// No explicit subscription; notifications arrive through participation or repo watching.
+    NotSubscribed,
+}
+
+/// Body of the thread and repository subscription endpoints.
+#[derive(Debug, Deserialize)]
+struct Subscription {
+    subscribed: bool,
+    ignored: bool,
+}
+
+/// Fetches the subscription state of a thread (`GET /notifications/threads/{id}/subscription`).
+pub async fn get_thread_subscription(
+    client: &Octocrab,
+    id: NotificationId,
+) -> Result<SubscriptionState, Error> {
+    let route = format!("/notifications/threads/{id}/subscription");
+    match client.get::<Subscription, _, ()>(route, None).await {
+        Ok(subscription) if subscription.ignored => Ok(SubscriptionState::Ignored),
+        Ok(subscription) if subscription.subscribed => Ok(SubscriptionState::Subscribed),
+        Ok(_) => Ok(SubscriptionState::NotSubscribed),
+        // GitHub answers 404 when the thread has no explicit subscription.
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            Ok(SubscriptionState::NotSubscribed)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Ignores all future notifications for a thread.
+pub async fn ignore_thread(client: &Octocrab, id: NotificationId) -> Result<(), Error> {
+    let route = format!("/notifications/threads/{id}/subscription");
+    let _: Subscription = client
+        .put(route, Some(&serde_json::json!({ "ignored": true })))
+        .await?;
+    Ok(())
+}
+
+/// How much activity of a watched repository reaches the inbox.
+///
+/// GitHub's API only knows "subscribed" and "ignored"; participating-only is the absence of a
+/// repository subscription, which is also what unwatching a repository does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchLevel {
+    All,
+    Participating,
+    Ignored,
+}
+
+/// A repository listed in `GET /user/subscriptions`.
+#[derive(Debug, Clone)]
+pub struct WatchedRepo {
+    pub full_name: String,
+    /// `None` when the level couldn't be read, for example on an organization repository
+    /// that restricts access.
+    pub level: Option<WatchLevel>,
+}
+
+/// Maximum number of repository subscription requests in flight at once.
+const WATCH_LEVEL_CONCURRENCY: usize = 4;
+
+/// Lists every repository the user is watching, along with its actual watch level.
+///
+/// A repository whose level can't be read is still listed, without a level.
+pub async fn list_watched_repos(client: &Octocrab) -> Result<Vec<WatchedRepo>, Error> {
+    let page: octocrab::Page<octocrab::models::Repository> = client
+        .get("/user/subscriptions", Some(&[("per_page", 100)]))
+        .await?;
+    let repos = client.all_pages(page).await?;
+
+    let permits = Arc::new(Semaphore::new(WATCH_LEVEL_CONCURRENCY));
+    let mut levels = JoinSet::new();
+    for full_name in repos.into_iter().filter_map(|repo| repo.full_name) {
+        let client = client.clone();
+        let permits = permits.clone();
+        levels.spawn(async move {
+            let _permit = permits.acquire().await;
+            let level = match get_watch_level(&client, &full_name).await {
+                Ok(level) => Some(level),
+                Err(error) => {
+                    eprintln!("failed to read the watch level of {full_name}: {error}");
+                    None
+                }
+            };
+            WatchedRepo { full_name, level }
+        });
+    }
+
+    let mut watched = Vec::new();
+    while let Some(result) = levels.join_next().await {
+        match result {
+            Ok(repo) => watched.push(repo),
+            Err(error) => eprintln!("watch level lookup panicked: {error}"),
+        }
+    }
+    Ok(watched)
+}
+
+/// Fetches the watch level of a repository given as `owner/repo`.
+pub async fn get_watch_level(client: &Octocrab, full_name: &str) -> Result<WatchLevel, Error> {
+    let route = format!("/repos/{full_name}/subscription");
+    match client.get::<Subscription, _, ()>(route, None).await {
+        Ok(subscription) if subscription.ignored => Ok(WatchLevel::Ignored),
+        Ok(subscription) if subscription.subscribed => Ok(WatchLevel::All),
+        Ok(_) => Ok(WatchLevel::Participating),
+        // GitHub answers 404 when the repository isn't watched.
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            Ok(WatchLevel::Participating)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Changes the watch level of a repository given as `owner/repo`.
+pub async fn set_watch_level(
+    client: &Octocrab,
+    full_name: &str,
+    level: WatchLevel,
+) -> Result<(), Error> {
+    let route = format!("/repos/{full_name}/subscription");
+    match level {
+        WatchLevel::Participating => {
+            let response = client._delete(route, None::<&()>).await?;
+            octocrab::map_github_error(response).await?;
+        }
+        WatchLevel::All | WatchLevel::Ignored => {
+            let body = serde_json::json!({
+                "subscribed": level == WatchLevel::All,
+                "ignored": level == WatchLevel::Ignored,
+            });
+            let _: serde_json::Value = client.put(route, Some(&body)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Web page of a notification's subject, falling back to the repository's page.
+///
+/// Releases link to the repository's releases, since the API URL only carries the release's
+/// id and the title needn't be its tag; [`resolve_notification_url`] finds the exact page.
+/// Discussions and workflow runs have no API URL at all, so they link to a search for their
+/// title.
 pub fn get_notification_url(notification: &Notification) -> Option<String> {
-    if let Some(url) = &notification.subject.url {
-        let url = url
-            .to_string()
-            .replace("api.github.com/repos", "github.com");
-
-        // Convert API URL to web URL
-        if url.contains("/pulls/") {
-            Some(
-                url.replace("api.github.com/repos", "github.com")
-                    .replace("/pulls/", "/pull/"),
-            )
-        } else if url.contains("/issues/") {
-            Some(
-                url.replace("api.github.com/repos", "github.com")
-                    .replace("/issues/", "/issues/"),
-            )
-        } else {
-            notification.repository.html_url.clone().map(Into::into)
+    let mut url = notification.repository.html_url.clone()?;
+    let subject = &notification.subject;
+    let last_segment = subject
+        .url
+        .as_ref()
+        .and_then(|url| url.path_segments()?.next_back().map(ToOwned::to_owned));
+
+    let mut query = None;
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.pop_if_empty();
+        match (subject.r#type.as_str(), last_segment.as_deref()) {
+            ("PullRequest", Some(number)) => {
+                path.extend(["pull", number]);
+            }
+            ("Issue", Some(number)) => {
+                path.extend(["issues", number]);
+            }
+            ("Commit", Some(sha)) => {
+                path.extend(["commit", sha]);
+            }
+            ("Release", _) => {
+                path.push("releases");
+            }
+            ("Discussion", _) => {
+                path.push("discussions");
+                query = Some(("discussions_q", subject.title.clone()));
+            }
+            ("CheckSuite", _) => {
+                path.push("actions");
+                query = workflow_run_query(&subject.title).map(|q| ("query", q));
+            }
+            ("RepositoryVulnerabilityAlert", _) => {
+                path.push("security");
+            }
+            ("RepositoryInvitation", _) => {
+                path.push("invitations");
+            }
+            _ => {}
         }
+    }
+
+    if let Some((key, value)) = query {
+        url.query_pairs_mut().append_pair(key, &value);
+    }
+    Some(url.into())
+}
+
+/// Actions search for a check suite titled like "CI workflow run failed for main branch".
+fn workflow_run_query(title: &str) -> Option<String> {
+    let (workflow, rest) = title.split_once(" workflow run ")?;
+    let (status, branch) = rest.split_once(" for ")?;
+    let branch = branch.strip_suffix(" branch")?;
+    let status = match status {
+        "failed" => "failure",
+        "succeeded" => "success",
+        status => status,
+    };
+    Some(format!("workflow:\"{workflow}\" is:{status} branch:{branch}"))
+}
+
+/// Exact web link of a notification, resolved through the API: the comment that triggered
+/// it, or the release's tag page, which can't be derived from the release's id.
+///
+/// Returns `None` when [`get_notification_url`] is already exact.
+pub async fn resolve_notification_url(
+    client: &Octocrab,
+    notification: &Notification,
+) -> Result<Option<String>, Error> {
+    if let Some(url) = resolve_comment_url(client, notification).await? {
+        return Ok(Some(url));
+    }
+
+    match &notification.subject.url {
+        Some(url) if notification.subject.r#type == "Release" => {
+            let release: WebPage = client.get(url.as_str(), None::<&()>).await?;
+            Ok(release.html_url)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Anything with a web page.
+#[derive(Deserialize)]
+struct WebPage {
+    html_url: Option<String>,
+}
+
+/// Web link to the comment, review or reply that triggered a notification, anchored on it
+/// (`#issuecomment-…`, `#discussion_r…`, `#pullrequestreview-…`, `#discussioncomment-…`).
+///
+/// Returns `None` when the notification isn't about a comment.
+async fn resolve_comment_url(
+    client: &Octocrab,
+    notification: &Notification,
+) -> Result<Option<String>, Error> {
+    if notification.subject.r#type == "Discussion" {
+        return resolve_discussion_comment_url(client, notification).await;
+    }
+
+    let Some(url) = notification
+        .subject
+        .latest_comment_url
+        .as_ref()
+        .filter(|url| Some(*url) != notification.subject.url.as_ref())
+    else {
+        return Ok(None);
+    };
+
+    let comment: WebPage = client.get(url.as_str(), None::<&()>).await?;
+    Ok(comment.html_url)
+}
+
+/// Discussion notifications carry no API URLs, so the discussion is looked up by title
+/// and linked at its latest comment or reply.
+///
+/// This is a best guess: the search matches the exact title, so a renamed discussion or one
+/// sharing its title with another can be missed or confused, and the link points at the last
+/// reply of the last top-level comment. A notification for a reply to an earlier comment,
+/// or for an edit, still lands there rather than on the comment that triggered it.
+async fn resolve_discussion_comment_url(
+    client: &Octocrab,
+    notification: &Notification,
+) -> Result<Option<String>, Error> {
+    #[derive(Deserialize)]
+    struct Response {
+        data: Option<Data>,
+    }
+    #[derive(Deserialize)]
+    struct Data {
+        search: Nodes<Discussion>,
+    }
+    #[derive(Deserialize)]
+    struct Nodes<T> {
+        nodes: Vec<Option<T>>,
+    }
+    #[derive(Deserialize)]
+    struct Discussion {
+        title: Option<String>,
+        url: Option<String>,
+        comments: Option<Nodes<DiscussionComment>>,
+    }
+    #[derive(Deserialize)]
+    struct DiscussionComment {
+        url: String,
+        replies: Option<Nodes<Reply>>,
+    }
+    #[derive(Deserialize)]
+    struct Reply {
+        url: String,
+    }
+
+    let Some(repository) = &notification.repository.full_name else {
+        return Ok(None);
+    };
+    let title = &notification.subject.title;
+    let query = r#"
+        query($q: String!) {
+          search(query: $q, type: DISCUSSION, first: 5) {
+            nodes {
+              ... on Discussion {
+                title url
+                comments(last: 1) { nodes { url replies(last: 1) { nodes { url } } } }
+              }
+            }
+          }
+        }
+    "#;
+    let search = format!("repo:{repository} in:title \"{}\"", title.replace('"', ""));
+    let response: Response = client
+        .graphql(&serde_json::json!({ "query": query, "variables": { "q": search } }))
+        .await?;
+
+    let Some(discussion) = response
+        .data
+        .into_iter()
+        .flat_map(|data| data.search.nodes)
+        .flatten()
+        .find(|discussion| discussion.title.as_deref() == Some(title.as_str()))
+    else {
+        return Ok(None);
+    };
+
+    let comment = discussion
+        .comments
+        .and_then(|comments| comments.nodes.into_iter().flatten().next_back());
+    Ok(match comment {
+        Some(comment) => Some(
+            comment
+                .replies
+                .and_then(|replies| replies.nodes.into_iter().flatten().next_back())
+                .map_or(comment.url, |reply| reply.url),
+        ),
+        None => discussion.url,
+    })
+}
+
+/// Short reference to a notification's subject within its repository: `#123` for issues,
+/// pull requests and discussions, the abbreviated SHA for commits.
+pub fn subject_reference(notification: &Notification) -> Option<String> {
+    let url = notification.subject.url.as_ref()?;
+    let segment = url.path_segments()?.next_back()?;
+    match notification.subject.r#type.as_str() {
+        "Issue" | "PullRequest" | "Discussion"
+            if segment.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            Some(format!("#{segment}"))
+        }
+        "Commit" => Some(segment.chars().take(7).collect()),
+        _ => None,
+    }
+}
+
+/// Reference GitHub links from anywhere: `owner/repo#123`, or `owner/repo@sha` for commits.
+pub fn subject_full_reference(notification: &Notification) -> Option<String> {
+    let repo = notification.repository.full_name.as_ref()?;
+    let reference = subject_reference(notification)?;
+    Some(if reference.starts_with('#') {
+        format!("{repo}{reference}")
     } else {
-        notification.repository.html_url.clone().map(Into::into)
+        format!("{repo}@{reference}")
+    })
+}
+
+/// Markdown link to a notification's subject, titled with the subject's title.
+pub fn subject_markdown_link(notification: &Notification) -> Option<String> {
+    let url = get_notification_url(notification)?;
+    let mut title = String::with_capacity(notification.subject.title.len());
+    for c in notification.subject.title.chars() {
+        if matches!(c, '\\' | '[' | ']') {
+            title.push('\\');
+        }
+        title.push(c);
     }
+    Some(format!("[{title}]({url})"))
 }
 
 pub fn format_reason(reason: &str) -> String {
@@ -44,3 +487,128 @@ pub fn format_reason(reason: &str) -> String {
         _ => reason.to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-written `GET /notifications` response in GitHub's format, with one thread per
+    /// subject type.
+    const NOTIFICATIONS: &str = include_str!("testdata/notifications.json");
+
+    fn notification(id: &str) -> Notification {
+        let notifications: Vec<Notification> = serde_json::from_str(NOTIFICATIONS).unwrap();
+        notifications
+            .into_iter()
+            .find(|n| n.id.to_string() == id)
+            .unwrap_or_else(|| panic!("no notification {id} in the fixture"))
+    }
+
+    #[test]
+    fn notification_urls() {
+        let repo = "https://github.com/pop-os/cosmic-epoch";
+        let cases = [
+            ("15321608801", format!("{repo}/pull/1412")),
+            ("15321608802", format!("{repo}/issues/1398")),
+            ("15321608803", format!("{repo}/releases")),
+            (
+                "15321608805",
+                format!("{repo}/commit/9f2a1c0d5be3e0b6ad6c4c4b1e2f4d1f0a7c3b21"),
+            ),
+            (
+                "15321608806",
+                format!("{repo}/discussions?discussions_q=Roadmap+for+tiling+%26+stacking"),
+            ),
+            (
+                "15321608807",
+                format!("{repo}/actions?query=workflow%3A%22Build%22+is%3Afailure+branch%3Amaster"),
+            ),
+            (
+                "15321608808",
+                format!(
+                    "{repo}/actions?query=workflow%3A%22Nightly%22+is%3Acancelled\
+                     +branch%3Arelease%2F1.0"
+                ),
+            ),
+            ("15321608809", format!("{repo}/security")),
+            ("15321608810", format!("{repo}/invitations")),
+        ];
+
+        for (id, expected) in cases {
+            assert_eq!(
+                get_notification_url(&notification(id)).as_deref(),
+                Some(expected.as_str()),
+                "notification {id}"
+            );
+        }
+    }
+
+    #[test]
+    fn subject_references() {
+        let repo = "https://github.com/pop-os/cosmic-epoch";
+        let cases = [
+            (
+                "15321608801",
+                Some("#1412"),
+                Some("pop-os/cosmic-epoch#1412"),
+                format!("[Add panel applet for GitHub notifications]({repo}/pull/1412)"),
+            ),
+            (
+                "15321608802",
+                Some("#1398"),
+                Some("pop-os/cosmic-epoch#1398"),
+                format!("[Applet crashes when the token expires]({repo}/issues/1398)"),
+            ),
+            (
+                "15321608803",
+                None,
+                None,
+                format!("[Epoch 1.0.0 alpha 7]({repo}/releases)"),
+            ),
+            (
+                "15321608805",
+                Some("9f2a1c0"),
+                Some("pop-os/cosmic-epoch@9f2a1c0"),
+                format!(
+                    "[Bump libcosmic]({repo}/commit/9f2a1c0d5be3e0b6ad6c4c4b1e2f4d1f0a7c3b21)"
+                ),
+            ),
+            (
+                "15321608806",
+                None,
+                None,
+                format!(
+                    "[Roadmap for tiling & stacking]\
+                     ({repo}/discussions?discussions_q=Roadmap+for+tiling+%26+stacking)"
+                ),
+            ),
+        ];
+
+        for (id, reference, full_reference, markdown) in cases {
+            let notification = notification(id);
+            assert_eq!(
+                subject_reference(&notification).as_deref(),
+                reference,
+                "notification {id}"
+            );
+            assert_eq!(
+                subject_full_reference(&notification).as_deref(),
+                full_reference,
+                "notification {id}"
+            );
+            assert_eq!(
+                subject_markdown_link(&notification),
+                Some(markdown),
+                "notification {id}"
+            );
+        }
+
+        let mut notification = notification("15321608802");
+        notification.subject.title = r"[RFC] Quote \ and ] in titles".to_string();
+        let title = r"[\[RFC\] Quote \\ and \] in titles]";
+        assert_eq!(
+            subject_markdown_link(&notification),
+            Some(format!("{title}({repo}/issues/1398)"))
+        );
+    }
+}
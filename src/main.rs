@@ -1,9 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 mod app;
+mod avatar;
+mod cache;
 mod config;
+mod enrichment;
 mod github;
 mod i18n;
+mod opener;
+mod snooze;
+mod state;
 
 fn main() -> cosmic::iced::Result {
     dotenv::dotenv().ok();
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Opens links in the browser, either directly or through a user-configured command.
+
+use octocrab::models::activity::Notification;
+
+use crate::github::get_notification_url;
+
+/// A link to open, along with the details available to opener command placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub url: String,
+    /// Repository as `owner/repo`.
+    pub repo: Option<String>,
+}
+
+impl Target {
+    pub fn new(url: impl Into<String>, repo: Option<String>) -> Self {
+        Self {
+            url: url.into(),
+            repo,
+        }
+    }
+
+    /// The web link of a notification's subject.
+    pub fn for_notification(notification: &Notification) -> Option<Self> {
+        get_notification_url(notification)
+            .map(|url| Self::new(url, notification.repository.full_name.clone()))
+    }
+
+    /// Issue or pull request number, taken from the last path segment of the URL.
+    pub fn number(&self) -> Option<&str> {
+        let path = self.url.split(['#', '?']).next()?;
+        path.rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()))
+    }
+}
+
+/// Splits an opener command on whitespace and fills in `{url}`, `{repo}` and `{number}`.
+///
+/// Quoting isn't supported; each placeholder expands within a single argument.
+pub fn expand(command: &str, target: &Target) -> Vec<String> {
+    command
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{url}", &target.url)
+                .replace("{repo}", target.repo.as_deref().unwrap_or_default())
+                .replace("{number}", target.number().unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Opens the target with `command`, or with the desktop's default handler if it's empty.
+///
+/// Inside Flatpak the default handler is the xdg-desktop-portal OpenURI interface, and
+/// custom commands are run on the host.
+pub async fn open(command: String, target: Target) -> Result<(), String> {
+    let args = expand(&command, &target);
+    let sandboxed = is_flatpak();
+
+    let Some((program, args)) = args.split_first() else {
+        return if sandboxed {
+            open_with_portal(&target.url).await
+        } else {
+            open::that_detached(&target.url).map_err(|e| e.to_string())
+        };
+    };
+
+    let mut process = if sandboxed {
+        let mut process = tokio::process::Command::new("flatpak-spawn");
+        process.arg("--host").arg(program);
+        process
+    } else {
+        tokio::process::Command::new(program)
+    };
+    process.args(args).spawn().map(drop).map_err(|e| e.to_string())
+}
+
+async fn open_with_portal(url: &str) -> Result<(), String> {
+    let uri = ashpd::url::Url::parse(url).map_err(|e| e.to_string())?;
+    ashpd::desktop::open_uri::OpenFileRequest::default()
+        .send_uri(&uri)
+        .await
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+fn is_flatpak() -> bool {
+    std::path::Path::new("/.flatpak-info").exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PULL: &str = "https://github.com/pop-os/cosmic-epoch/pull/1412";
+
+    #[test]
+    fn numbers() {
+        let cases = [
+            (PULL.to_string(), Some("1412")),
+            (format!("{PULL}#issuecomment-2519871234"), Some("1412")),
+            (format!("{PULL}?diff=split#discussion_r1865"), Some("1412")),
+            ("https://github.com/pop-os/cosmic-epoch/issues/1398".to_string(), Some("1398")),
+            ("https://github.com/pop-os/cosmic-epoch/releases".to_string(), None),
+            ("https://github.com/pop-os/cosmic-epoch/commit/9f2a1c0".to_string(), None),
+            ("https://github.com/pop-os/cosmic-epoch/discussions#1412".to_string(), None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(Target::new(url.as_str(), None).number(), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn expansions() {
+        let repo = Some("pop-os/cosmic-epoch".to_string());
+        let cases = [
+            (
+                "gh pr view {number} --repo {repo} --web",
+                Target::new(PULL, repo.clone()),
+                vec!["gh", "pr", "view", "1412", "--repo", "pop-os/cosmic-epoch", "--web"],
+            ),
+            (
+                "firefox  --new-tab   {url}",
+                Target::new(format!("{PULL}#issuecomment-2519871234"), None),
+                vec![
+                    "firefox",
+                    "--new-tab",
+                    "https://github.com/pop-os/cosmic-epoch/pull/1412#issuecomment-2519871234",
+                ],
+            ),
+            (
+                "review --target={repo}#{number}",
+                Target::new(PULL, repo),
+                vec!["review", "--target=pop-os/cosmic-epoch#1412"],
+            ),
+            (
+                "review {repo} {number}",
+                Target::new("https://github.com/pop-os/cosmic-epoch/releases", None),
+                vec!["review", "", ""],
+            ),
+            ("", Target::new(PULL, None), vec![]),
+        ];
+
+        for (command, target, expected) in cases {
+            assert_eq!(expand(command, &target), expected, "{command:?}");
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Format accepted for custom snooze times, in local time.
+pub const CUSTOM_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// A thread hidden from the inbox until `until`.
+///
+/// The subject is kept so the thread can be announced when it wakes up, even if it has
+/// dropped out of the fetched list in the meantime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snooze {
+    pub until: DateTime<Utc>,
+    pub title: String,
+    pub repository: Option<String>,
+    /// The thread's `updated_at` when it was snoozed.
+    ///
+    /// Snoozes saved before this was recorded default to the epoch, so they
+    /// still wake but don't hold the thread unread.
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Snooze {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.until
+    }
+}
+
+/// Quick choices offered by the Snooze menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozePreset {
+    OneHour,
+    ThisEvening,
+    Tomorrow,
+    NextWeek,
+}
+
+impl SnoozePreset {
+    pub const ALL: [SnoozePreset; 4] = [
+        SnoozePreset::OneHour,
+        SnoozePreset::ThisEvening,
+        SnoozePreset::Tomorrow,
+        SnoozePreset::NextWeek,
+    ];
+
+    /// When a thread snoozed at `now` with this preset should resurface.
+    pub fn wake_time(self, now: DateTime<Local>) -> DateTime<Utc> {
+        let evening = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let morning = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let today = now.date_naive();
+
+        let wake = match self {
+            SnoozePreset::OneHour => return (now + Duration::hours(1)).with_timezone(&Utc),
+            SnoozePreset::ThisEvening if now.time() < evening => today.and_time(evening),
+            SnoozePreset::ThisEvening | SnoozePreset::Tomorrow => {
+                (today + Duration::days(1)).and_time(morning)
+            }
+            SnoozePreset::NextWeek => {
+                let days = 7 - today.weekday().num_days_from_monday();
+                (today + Duration::days(i64::from(days))).and_time(morning)
+            }
+        };
+
+        to_utc(wake).unwrap_or_else(|| (now + Duration::hours(1)).with_timezone(&Utc))
+    }
+}
+
+/// Parses a custom snooze time in [`CUSTOM_FORMAT`], rejecting times in the past.
+pub fn parse_custom(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let naive = chrono::NaiveDateTime::parse_from_str(input.trim(), CUSTOM_FORMAT).ok()?;
+    to_utc(naive).filter(|wake| *wake > now)
+}
+
+fn to_utc(naive: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// Shows a desktop notification for a thread that just woke up.
+pub async fn announce(snooze: Snooze) {
+    let _ = tokio::task::spawn_blocking(move || {
+        notify_rust::Notification::new()
+            .appname("Git Work")
+            .icon("dev.edfloreshz.GitWork")
+            .summary(&snooze.title)
+            .body(snooze.repository.as_deref().unwrap_or_default())
+            .show()
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A local time written in [`CUSTOM_FORMAT`], so the cases hold in any time zone.
+    fn local(time: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(time, CUSTOM_FORMAT).unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    #[test]
+    fn wake_times() {
+        // 2026-10-12 is a Monday.
+        let cases = [
+            (SnoozePreset::OneHour, "2026-10-14 10:30", "2026-10-14 11:30"),
+            (SnoozePreset::ThisEvening, "2026-10-14 17:59", "2026-10-14 18:00"),
+            (SnoozePreset::ThisEvening, "2026-10-14 18:00", "2026-10-15 09:00"),
+            (SnoozePreset::ThisEvening, "2026-10-14 23:30", "2026-10-15 09:00"),
+            (SnoozePreset::Tomorrow, "2026-10-14 00:10", "2026-10-15 09:00"),
+            (SnoozePreset::Tomorrow, "2026-10-18 23:59", "2026-10-19 09:00"),
+            (SnoozePreset::NextWeek, "2026-10-12 08:00", "2026-10-19 09:00"),
+            (SnoozePreset::NextWeek, "2026-10-16 12:00", "2026-10-19 09:00"),
+            (SnoozePreset::NextWeek, "2026-10-18 23:00", "2026-10-19 09:00"),
+        ];
+
+        for (preset, now, expected) in cases {
+            assert_eq!(
+                preset.wake_time(local(now)),
+                local(expected).with_timezone(&Utc),
+                "{preset:?} at {now}"
+            );
+        }
+    }
+
+    #[test]
+    fn custom_times() {
+        let now = local("2026-10-14 10:30").with_timezone(&Utc);
+        let cases = [
+            ("2026-10-14 10:31", Some("2026-10-14 10:31")),
+            ("  2026-10-15 09:00 ", Some("2026-10-15 09:00")),
+            ("2026-10-14 10:30", None),
+            ("2026-10-13 09:00", None),
+            ("2026-10-14", None),
+            ("2026-13-01 09:00", None),
+            ("tomorrow", None),
+            ("", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_custom(input, now),
+                expected.map(|time| local(time).with_timezone(&Utc)),
+                "{input:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use octocrab::models::activity::Notification;
+use octocrab::models::NotificationId;
+use serde::{Deserialize, Serialize};
+
+use crate::snooze::Snooze;
+
+/// Local data that survives restarts but isn't user configuration.
+#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 1]
+pub struct State {
+    /// Threads hidden from the inbox until their wake time.
+    pub snoozed: HashMap<NotificationId, Snooze>,
+    /// Read states set locally, which take precedence over GitHub's `unread` flag.
+    pub read_overrides: HashMap<NotificationId, ReadOverride>,
+    /// Threads saved for later, most recently saved first.
+    pub saved: Vec<SavedThread>,
+}
+
+/// A locally pinned read state for one version of a thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadOverride {
+    pub unread: bool,
+    /// The thread's `updated_at` when the override was set; newer activity discards it.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Applies the local read-state overlay on top of GitHub's `unread` flags.
+///
+/// Overrides are dropped once their thread has newer activity, or once GitHub agrees that a
+/// thread is read. With `fresh`, `notifications` is assumed to be a fresh fetch: read
+/// overrides for absent threads are dropped, and the threads kept unread locally but missing
+/// from the list are returned so they can be fetched individually.
+pub fn reconcile_read_overrides(
+    overrides: &mut HashMap<NotificationId, ReadOverride>,
+    notifications: &mut [Notification],
+    fresh: bool,
+) -> Vec<NotificationId> {
+    let mut missing = Vec::new();
+    overrides.retain(|id, read_override| {
+        match notifications.iter_mut().find(|n| n.id == *id) {
+            Some(notification) if notification.updated_at > read_override.updated_at => false,
+            Some(notification) if fresh && !read_override.unread && !notification.unread => false,
+            Some(notification) => {
+                notification.unread = read_override.unread;
+                true
+            }
+            None if !fresh => true,
+            None if read_override.unread => {
+                missing.push(*id);
+                true
+            }
+            None => false,
+        }
+    });
+    missing
+}
+
+/// A thread pinned to the Saved tab.
+///
+/// Keeps everything needed to show and open it, since the thread may be read or done on
+/// GitHub long before it is unsaved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedThread {
+    pub id: NotificationId,
+    pub title: String,
+    pub url: Option<String>,
+    pub repository: Option<String>,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTIFICATIONS: &str = include_str!("testdata/notifications.json");
+
+    #[test]
+    fn read_overrides() {
+        let notifications: Vec<Notification> = serde_json::from_str(NOTIFICATIONS).unwrap();
+        let thread = notifications[0].clone();
+        let seen = thread.updated_at;
+        let before = seen - chrono::TimeDelta::hours(1);
+
+        // (case, GitHub's unread flag or None when the thread isn't listed, override, fresh,
+        //  whether the override is kept, resulting unread flag, whether it's refetched)
+        let cases = [
+            ("newer activity", Some(true), (false, before), true, false, Some(true), false),
+            ("stale, partial list", Some(false), (true, before), false, false, Some(false), false),
+            ("read on GitHub too", Some(false), (false, seen), true, false, Some(false), false),
+            ("read locally", Some(true), (false, seen), true, true, Some(false), false),
+            ("unread locally", Some(false), (true, seen), true, true, Some(true), false),
+            ("unread and missing", None, (true, seen), true, true, None, true),
+            ("read and missing", None, (false, seen), true, false, None, false),
+            ("missing from a partial list", None, (true, seen), false, true, None, false),
+        ];
+
+        for (case, github_unread, (unread, updated_at), fresh, kept, expected, refetched) in cases
+        {
+            let mut listed: Vec<_> = github_unread
+                .map(|unread| {
+                    let mut listed = thread.clone();
+                    listed.unread = unread;
+                    listed
+                })
+                .into_iter()
+                .collect();
+            let mut overrides =
+                HashMap::from([(thread.id, ReadOverride { unread, updated_at })]);
+
+            let missing = reconcile_read_overrides(&mut overrides, &mut listed, fresh);
+
+            assert_eq!(overrides.contains_key(&thread.id), kept, "{case}: kept");
+            assert_eq!(listed.first().map(|n| n.unread), expected, "{case}: unread");
+            assert_eq!(missing == [thread.id], refetched, "{case}: refetched");
+        }
+    }
+}